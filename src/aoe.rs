@@ -2,11 +2,7 @@
 use bevy::{core::Zeroable, input::mouse::MouseMotion, prelude::*, window::PrimaryWindow};
 use bevy_mod_raycast::{immediate::Raycast, CursorRay};
 
-use crate::{character_controller::Player, health_bars::PrimaryCamera, Floor, lifetime::Lifetime, damage::Damage, projectile::Projectile};
-
-pub trait GroundCastSpell {
-    fn add_ground_target(&self, target: Vec3);
-}
+use crate::{character_controller::Player, health_bars::PrimaryCamera, Floor, damage::DamageRequest, death::Dead, enemy::Enemy, health::Health, pulse::PulseTargets, spells::{CastSpellInit, CastTarget, SpellId, SpellSchool}, utils::xz_distance};
 
 #[derive(Event)]
 pub struct RayCastEvent {
@@ -17,13 +13,14 @@ pub struct RayCastEvent {
 #[derive(Component)]
 pub struct Targeting {
     position: Vec3,
+    spell_id: SpellId,
 }
 
-/// Initiate Ground Targeting
+/// Initiate Ground Targeting for a spell that is aimed at a point on the ground
 #[derive(Event)]
 pub struct GroundTargetInitEvent {
-    spell: Box<dyn GroundCastSpell + Send + Sync>,
-} // This struct should either have intent details or spell casting
+    pub spell_id: SpellId,
+}
 
 #[derive(Component)]
 pub struct Pointer;
@@ -38,6 +35,7 @@ pub fn ground_targeting_system(
     for event in events.read() {
         commands.entity(player.single()).insert(Targeting {
             position: Vec3::zeroed(),
+            spell_id: event.spell_id.clone(),
         });
     }
 }
@@ -84,60 +82,79 @@ pub fn targeting_system(
 pub fn targeting_click_system(
     targeting: Query<&Targeting>,
     buttons: Res<Input<MouseButton>>,
-    mut player: Query<(Entity, &Transform), With<Player>>,
+    keyboard_input: Res<Input<KeyCode>>,
+    player: Query<Entity, With<Player>>,
     mut commands: Commands,
-
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut spell_writer: EventWriter<CastSpellInit>,
 ) {
     if let Ok(targeting) = targeting.get_single() {
-        if let Ok((player, character_transform)) = player.get_single_mut() {
+        if let Ok(player) = player.get_single() {
             if buttons.just_pressed(MouseButton::Left) {
-                commands
-                    .spawn(
-                        PbrBundle {
-                            mesh: meshes.add(Mesh::from(shape::UVSphere {
-                                radius: 0.2,
-                                stacks: 18,
-                                sectors: 36,
-                            })),
-                            material: materials.add(Color::WHITE.into()),
-                            transform: Transform::from_translation(character_transform.translation),
-                            ..default()
-                        },
-                    )
-                    .insert(Lifetime {
-                        timer: Timer::from_seconds(30.0, TimerMode::Once),
-                    })
-                    .insert(Name::new("Bullet"))
-                    .insert(Damage { amount: 10 })
-                    .insert(Projectile {
-                        despawn_after_hit: true,
-                        speed: 0.5,
-                        direction: (targeting.position - character_transform.translation).normalize() * 3.0,
-                    });
+                spell_writer.send(CastSpellInit {
+                    spell_id: targeting.spell_id.clone(),
+                    target: CastTarget::Point(targeting.position),
+                });
 
+                commands.entity(player).remove::<Targeting>();
+            } else if keyboard_input.just_pressed(KeyCode::Escape) {
                 commands.entity(player).remove::<Targeting>();
             }
         }
     }
 }
 
-/// Remove this
-struct GroundTargetSpell;
-
-impl GroundCastSpell for GroundTargetSpell {
-    fn add_ground_target(&self, target: Vec3) {}
+/// An area on the ground that damages every enemy of its source standing in it on every tick.
+#[derive(Component)]
+pub struct GroundEffect {
+    pub source: Entity,
+    pub spell_id: SpellId,
+    pub school: SpellSchool,
+    pub radius: f32,
+    pub damage: u32,
+    pub tick: Timer,
 }
 
-pub fn target_init_system(
-    keyboard_input: Res<Input<KeyCode>>,
-    mut writer: EventWriter<GroundTargetInitEvent>,
+pub fn ground_effect_system(
+    time: Res<Time>,
+    mut damage_requests: EventWriter<DamageRequest>,
+    mut gizmos: Gizmos,
+    mut ground_effects: Query<(&Transform, &mut GroundEffect)>,
+    units: Query<
+        (Entity, &Transform, Has<Enemy>),
+        (With<Health>, Without<GroundEffect>, Without<Dead>),
+    >,
+    factions: Query<Has<Enemy>>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::F) {
-        writer.send(GroundTargetInitEvent {
-            spell: Box::new(GroundTargetSpell {}),
-        })
+    for (transform, mut ground_effect) in &mut ground_effects {
+        gizmos.circle(
+            transform.translation,
+            Vec3::Y,
+            ground_effect.radius,
+            Color::ORANGE_RED,
+        );
+
+        ground_effect.tick.tick(time.delta());
+
+        let Ok(source_is_enemy) = factions.get(ground_effect.source) else {
+            continue;
+        };
+
+        for _ in 0..ground_effect.tick.times_finished_this_tick() {
+            for (entity, unit_transform, unit_is_enemy) in &units {
+                if PulseTargets::Enemies.includes(source_is_enemy, unit_is_enemy)
+                    && xz_distance(transform.translation, unit_transform.translation)
+                        < ground_effect.radius
+                {
+                    damage_requests.send(DamageRequest::new(
+                        Some(ground_effect.source),
+                        entity,
+                        &ground_effect.spell_id,
+                        ground_effect.school,
+                        ground_effect.damage,
+                    ));
+                }
+            }
+        }
     }
 }
 
@@ -149,9 +166,9 @@ impl Plugin for AoeTargetingPlugin {
             Update,
             (
                 ground_targeting_system,
-                target_init_system,
                 targeting_system,
                 targeting_click_system,
+                ground_effect_system,
             ),
         );
     }
@@ -2,158 +2,650 @@
 // Impair movement
 // DoT/HoT
 // Increase damage done/taken
+// Crowd control
+// Absorb damage
 
-use bevy::prelude::*;
+use std::{mem::discriminant, time::Duration};
+
+use bevy::{prelude::*, utils::HashMap};
+use serde::Deserialize;
 
 use crate::{
-    damage::{apply_damage, apply_health},
-    health::Health,
+    crowd_control::{fear_system, CrowdControl, DiminishingReturns},
+    damage::{DamageModifiers, DamageRequest, HealRequest},
+    periodic::{whole_amount, PeriodicTicks},
+    pulse::{aura_pulse_system, pulse_gizmo_system, AuraPulsed, PulseTargets},
+    spells::{duration_from_secs, SpellEffect, SpellId, SpellSchool},
 };
 
-pub enum Aura {
-    // MovementEffect,
-    // OvertimeEffect,
-    // DamageChange,
+/// An aura as a spell declares it.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Aura {
+    pub effect: AuraEffect,
+    #[serde(deserialize_with = "duration_from_secs")]
+    pub duration: Duration,
+    #[serde(default = "one_stack")]
+    pub max_stacks: u32,
+    #[serde(default)]
+    pub refresh: RefreshPolicy,
+    #[serde(default)]
+    pub dispel: DispelType,
+    /// Harmful auras are what a cleanse removes, helpful ones what a purge removes.
+    #[serde(default)]
+    pub harmful: bool,
+    #[serde(default)]
+    pub scaling: AuraScaling,
+}
+
+fn one_stack() -> u32 {
+    1
+}
+
+fn one_second() -> Duration {
+    Duration::from_secs(1)
+}
+
+/// What an aura does for as long as it is applied. Every stack adds the effect once more.
+#[derive(Debug, Clone, Deserialize)]
+pub enum AuraEffect {
+    /// Slows (or, with `increase`, hastes) movement by `percentage_change` percent.
+    Movement {
+        percentage_change: u8,
+        #[serde(default)]
+        increase: bool,
+    },
+    /// Ticks `amount` of damage (or healing) `every` so often, one second by default.
+    /// Haste shortens the interval, and a final tick cut short by the aura ending is partial.
+    Overtime {
+        amount: u32,
+        #[serde(default)]
+        healing: bool,
+        #[serde(default = "one_second", deserialize_with = "duration_from_secs")]
+        every: Duration,
+    },
+    /// Changes the damage the target deals or takes, by a flat amount or a percentage.
+    DamageChange {
+        direction: DamageChangeDirection,
+        amount: i32, // negative values reduce the damage
+        value_type: DamageChangeType,
+    },
+    /// Increases the target's haste by `percentage` percent, which speeds up casts and periodic ticks.
+    Haste { percentage: u32 },
+    /// Stuns, roots, silences or fears the target. Subject to diminishing returns.
+    CrowdControl(CrowdControl),
+    /// Soaks up `amount` of damage before it reaches health, and pops once used up.
+    Absorb { amount: u32 },
+    /// Resolves `effects` on every unit within `radius` of the holder, `every` so often.
+    Pulse {
+        radius: f32,
+        #[serde(default = "one_second", deserialize_with = "duration_from_secs")]
+        every: Duration,
+        targets: PulseTargets,
+        effects: Vec<SpellEffect>,
+    },
 }
 
-/// OVERTIME
+/// What happens when an aura is applied to a target that already has it from the same caster.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum RefreshPolicy {
+    /// Start the duration over.
+    #[default]
+    Refresh,
+    /// Add the full duration on top of whatever is left.
+    Extend,
+    /// Add a stack, up to the aura's max stacks, and start the duration over.
+    Stack,
+}
 
-#[derive(Component)]
-pub struct OvertimeComponent {
-    pub applied: Vec<Overtime>,
+/// When the caster's damage and haste modifiers are read for an aura's periodic ticks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum AuraScaling {
+    /// Every tick uses the caster's modifiers at that moment.
+    #[default]
+    Dynamic,
+    /// Every tick uses the caster's modifiers from when the aura was applied.
+    Snapshot,
 }
 
-pub struct Overtime {
-    pub damage_healing_flag: bool, // true -> healing, false -> damage
-    pub amount: u32,
-    pub every: u32,
-    pub time_unit: OvertimeUnit,
-    pub timer: Timer,
-    pub count: u32,
+/// The caster modifiers that scale an aura's periodic ticks.
+#[derive(Debug, Clone, Copy)]
+pub struct CasterModifiers {
+    pub haste: f32,
+    pub damage_dealt: DamageModifiers,
 }
 
-impl Overtime {
-    pub fn damage_per_second(amount: u32, count: u32) -> Self {
-        Overtime {
-            amount,
-            damage_healing_flag: false,
-            every: 1,
-            time_unit: OvertimeUnit::Second,
-            timer: Timer::from_seconds(1.0, TimerMode::Repeating),
-            count,
+impl Default for CasterModifiers {
+    fn default() -> Self {
+        CasterModifiers {
+            haste: 1.0,
+            damage_dealt: DamageModifiers::default(),
         }
     }
 }
 
-pub enum OvertimeUnit {
-    Second,
-    // Hour,
-    // Minute,
+/// Which dispels can remove an aura.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum DispelType {
+    /// Can't be dispelled.
+    #[default]
+    None,
+    Magic,
+    Poison,
+    Disease,
+    Curse,
 }
 
-pub fn apply_overtime(
-    entity: Entity,
-    commands: &mut Commands,
-    overtime: Overtime,
-    applied: &mut Option<Mut<OvertimeComponent>>,
-) {
-    if let Some(comp) = applied {
-        comp.applied.push(overtime);
-    } else {
-        let mut applied = Vec::with_capacity(100);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum DamageChangeDirection {
+    Dealt,
+    Taken,
+}
 
-        applied.push(overtime);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum DamageChangeType {
+    Percent,
+    Amount,
+}
 
-        commands
-            .entity(entity)
-            .insert(OvertimeComponent { applied });
-    }
+/// An aura that is applied to a unit.
+pub struct AuraInstance {
+    /// Whoever applied the aura, if anyone.
+    pub source: Option<Entity>,
+    pub spell_id: SpellId,
+    /// School of the spell that applied the aura, which its damage over time is of.
+    pub school: SpellSchool,
+    pub effect: AuraEffect,
+    pub duration: Timer,
+    pub stacks: u32,
+    pub max_stacks: u32,
+    pub refresh: RefreshPolicy,
+    pub dispel: DispelType,
+    pub harmful: bool,
+    pub scaling: AuraScaling,
+    /// Damage a shield can still soak up.
+    pub absorb_left: u32,
+    /// When an over time or pulsing aura ticks next.
+    ticks: Option<PeriodicTicks>,
+    /// Fraction of a point of damage or healing carried over from earlier ticks, so that only
+    /// whole points are dealt, see [`whole_amount`].
+    remainder: f32,
+    /// The caster's modifiers from when the aura was applied, for snapshotting auras.
+    snapshot: Option<CasterModifiers>,
+    /// Set when the aura is taken off early, e.g. dispelled, ahead of the [`aura_system`]
+    /// removing it.
+    removed: Option<AuraRemovedReason>,
 }
 
-pub fn _remove_overtime(
-    entity: Entity,
-    commands: &mut Commands,
-    overtime: Overtime,
-    applied: &mut Option<OvertimeComponent>,
-) {
-    // let ent_commands = commands.entity(entity);
+impl AuraInstance {
+    pub fn new(
+        aura: &Aura,
+        source: Option<Entity>,
+        spell_id: SpellId,
+        school: SpellSchool,
+    ) -> Self {
+        let mut instance = AuraInstance {
+            source,
+            spell_id,
+            school,
+            effect: aura.effect.clone(),
+            duration: Timer::new(aura.duration, TimerMode::Once),
+            stacks: 1,
+            max_stacks: aura.max_stacks.max(1),
+            refresh: aura.refresh,
+            dispel: aura.dispel,
+            harmful: aura.harmful,
+            scaling: aura.scaling,
+            absorb_left: 0,
+            ticks: match aura.effect {
+                AuraEffect::Overtime { every, .. } | AuraEffect::Pulse { every, .. } => {
+                    Some(PeriodicTicks::new(every))
+                }
+                _ => None,
+            },
+            snapshot: None,
+            removed: None,
+            remainder: 0.0,
+        };
+
+        instance.refill_absorb();
+        instance
+    }
+
+    /// Scales the aura's ticks by the modifiers of its caster at the moment it is applied,
+    /// which [`AuraScaling::Snapshot`] auras keep for their whole duration.
+    pub fn with_caster(mut self, caster: CasterModifiers) -> Self {
+        self.update_caster(caster);
+
+        if self.scaling == AuraScaling::Snapshot {
+            self.snapshot = Some(caster);
+        }
 
-    if let Some(comp) = applied {
-        comp.applied.push(overtime);
-    } else {
-        let mut applied = Vec::with_capacity(100);
+        self
+    }
+
+    /// Picks up the caster's current modifiers, unless the aura snapshotted them.
+    fn update_caster(&mut self, caster: CasterModifiers) {
+        if self.snapshot.is_some() {
+            return;
+        }
 
-        applied.push(overtime);
+        if let Some(ticks) = self.ticks.as_mut() {
+            ticks.set_haste(caster.haste);
+        }
+    }
 
-        commands
-            .entity(entity)
-            .insert(OvertimeComponent { applied });
+    /// Fills a shield back up to its full (stacked) amount.
+    fn refill_absorb(&mut self) {
+        if let AuraEffect::Absorb { amount } = self.effect {
+            self.absorb_left = amount * self.stacks;
+        }
+    }
+
+    /// Why the aura should be removed, if it should.
+    fn removal_reason(&self) -> Option<AuraRemovedReason> {
+        if self.removed.is_some() {
+            self.removed
+        } else if self.duration.finished() {
+            Some(AuraRemovedReason::Expired)
+        } else if matches!(self.effect, AuraEffect::Absorb { .. }) && self.absorb_left == 0 {
+            Some(AuraRemovedReason::Depleted)
+        } else {
+            None
+        }
+    }
+
+    /// Re-applies the aura according to its refresh policy. Snapshotting auras take the
+    /// caster's modifiers from the new application.
+    fn reapply(&mut self, incoming: &AuraInstance) {
+        let duration = incoming.duration.duration();
+
+        match self.refresh {
+            RefreshPolicy::Refresh => self.duration = Timer::new(duration, TimerMode::Once),
+            RefreshPolicy::Extend => {
+                let remaining = self.duration.remaining();
+                self.duration = Timer::new(remaining + duration, TimerMode::Once);
+            }
+            RefreshPolicy::Stack => {
+                self.stacks = (self.stacks + 1).min(self.max_stacks);
+                self.duration = Timer::new(duration, TimerMode::Once);
+            }
+        }
+
+        self.refill_absorb();
+
+        if let (AuraScaling::Snapshot, Some(snapshot)) = (self.scaling, incoming.snapshot) {
+            self.snapshot = Some(snapshot);
+
+            if let Some(ticks) = self.ticks.as_mut() {
+                ticks.set_haste(snapshot.haste);
+            }
+        }
     }
 }
 
-fn overtime_system(
-    mut entities: Query<(Entity, &mut Health, &mut OvertimeComponent)>,
-    time: Res<Time>,
-    mut commands: Commands,
-) {
-    for (entity, mut health, mut overtime_comp) in entities.iter_mut() {
-        for overtime in (*overtime_comp.applied).iter_mut() {
-            if overtime.timer.just_finished() {
-                if overtime.damage_healing_flag {
-                    apply_health(&mut commands, entity, overtime.amount, &mut health)
-                } else {
-                    apply_damage(&mut commands, entity, overtime.amount, &mut health)
-                }
+/// Every aura applied to a unit. Every unit with [`Health`](crate::health::Health) is spawned
+/// with one, so that auras applied to it within the same frame all end up on the same component.
+#[derive(Component, Default)]
+pub struct Auras {
+    applied: Vec<AuraInstance>,
+    diminishing_returns: DiminishingReturns,
+}
 
-                overtime.count -= 1;
+impl Auras {
+    /// Applies the aura, or re-applies it if the same caster already applied the same kind of aura
+    /// with the same spell.
+    /// Returns the (new or updated) instance, or `None` if the unit is immune to it.
+    pub fn apply(&mut self, mut instance: AuraInstance) -> Option<&AuraInstance> {
+        if let AuraEffect::CrowdControl(crowd_control) = instance.effect {
+            let multiplier = self.diminishing_returns.apply(crowd_control)?;
+            let duration = instance.duration.duration().mul_f32(multiplier);
 
-                // if overtime.count < 1 {
+            instance.duration = Timer::new(duration, TimerMode::Once);
+        }
+
+        let existing = self.applied.iter().position(|applied| {
+            applied.spell_id == instance.spell_id
+                && applied.source == instance.source
+                && discriminant(&applied.effect) == discriminant(&instance.effect)
+        });
 
-                //     // add all of the depleted timings to the end
-                //     overtime_comp.applied.swap(i, (overtime_comp.applied.len() - 1));
-                //     remove_count += 1;
-                // }
+        match existing {
+            Some(index) => {
+                self.applied[index].reapply(&instance);
+                self.applied.get(index)
             }
+            None => {
+                self.applied.push(instance);
+                self.applied.last()
+            }
+        }
+    }
+
+    fn crowd_controls(&self) -> impl Iterator<Item = CrowdControl> + '_ {
+        self.applied.iter().filter_map(|aura| match aura.effect {
+            AuraEffect::CrowdControl(crowd_control) => Some(crowd_control),
+            _ => None,
+        })
+    }
 
-            overtime.timer.tick(time.delta());
+    /// The first crowd control on the unit that `blocks`, e.g. [`CrowdControl::blocks_casting`].
+    pub fn crowd_controlled_by(&self, blocks: fn(&CrowdControl) -> bool) -> Option<CrowdControl> {
+        self.crowd_controls().find(blocks)
+    }
+
+    /// Soaks up as much of `amount` as the unit's shields can, oldest shield first.
+    /// Returns how much was absorbed. Used up shields are removed by the [`aura_system`].
+    pub fn absorb(&mut self, amount: u32) -> u32 {
+        let mut absorbed = 0;
+
+        for aura in self.applied.iter_mut() {
+            // dispelled shields stop soaking right away, not once the aura system removes them
+            if !matches!(aura.effect, AuraEffect::Absorb { .. })
+                || aura.duration.finished()
+                || aura.removed.is_some()
+            {
+                continue;
+            }
+
+            let soaked = aura.absorb_left.min(amount - absorbed);
+
+            aura.absorb_left -= soaked;
+            absorbed += soaked;
         }
 
-        // removed depleted dps
-        overtime_comp.applied.retain(|value| value.count > 0);
+        absorbed
+    }
 
-        // remove the buff if there are no overtimes working on the thing
-        if overtime_comp.applied.is_empty() {
-            if let Some(mut ent) = commands.get_entity(entity) {
-                ent.remove::<OvertimeComponent>();
+    /// Dispels up to `count` auras of the given type, oldest first, and returns how many it found.
+    /// Dispelled auras are removed by the [`aura_system`] like any other aura.
+    pub fn dispel(&mut self, dispel: DispelType, harmful: bool, count: u32) -> usize {
+        if dispel == DispelType::None {
+            return 0;
+        }
+
+        let mut dispelled = 0;
+
+        for aura in self.applied.iter_mut() {
+            if dispelled == count as usize {
+                break;
+            }
+
+            if aura.dispel == dispel && aura.harmful == harmful && aura.removed.is_none() {
+                aura.removed = Some(AuraRemovedReason::Dispelled);
+                dispelled += 1;
             }
+        }
+
+        dispelled
+    }
+
+    /// Takes every aura off a unit that died. They are removed by the [`aura_system`].
+    pub fn clear_on_death(&mut self) {
+        for aura in self.applied.iter_mut() {
+            aura.removed.get_or_insert(AuraRemovedReason::Died);
+        }
+    }
+
+    /// Every aura on the unit, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &AuraInstance> {
+        self.applied.iter()
+    }
+
+    /// The fear the unit is under, if any.
+    pub fn fear(&self) -> Option<&AuraInstance> {
+        self.applied
+            .iter()
+            .find(|aura| matches!(aura.effect, AuraEffect::CrowdControl(CrowdControl::Fear)))
+    }
+
+    /// What movement speed is multiplied by with every applied aura taken into account.
+    /// Auras don't stack with each other: only the strongest slow and the strongest haste count.
+    pub fn speed_multiplier(&self) -> f32 {
+        let strongest = |increase: bool| {
+            self.applied
+                .iter()
+                .filter_map(|aura| match aura.effect {
+                    AuraEffect::Movement {
+                        percentage_change,
+                        increase: aura_increase,
+                    } if aura_increase == increase => {
+                        Some((percentage_change as u32 * aura.stacks).min(100) as f32 / 100.0)
+                    }
+                    _ => None,
+                })
+                .fold(0.0, f32::max)
         };
+
+        (1.0 + strongest(true)) * (1.0 - strongest(false))
+    }
+
+    /// What cast times and tick intervals are divided by, e.g. 1.2 for 20% haste.
+    pub fn haste(&self) -> f32 {
+        let percentage: u32 = self
+            .applied
+            .iter()
+            .filter_map(|aura| match aura.effect {
+                AuraEffect::Haste { percentage } => Some(percentage * aura.stacks),
+                _ => None,
+            })
+            .sum();
+
+        1.0 + percentage as f32 / 100.0
+    }
+
+    /// The modifiers of this unit that scale the auras it applies.
+    pub fn caster_modifiers(&self) -> CasterModifiers {
+        CasterModifiers {
+            haste: self.haste(),
+            damage_dealt: self.damage_modifiers(DamageChangeDirection::Dealt),
+        }
+    }
+
+    fn damage_change(&self, direction: DamageChangeDirection, value_type: DamageChangeType) -> i32 {
+        self.applied
+            .iter()
+            .filter_map(|aura| match aura.effect {
+                AuraEffect::DamageChange {
+                    direction: aura_direction,
+                    amount,
+                    value_type: aura_value_type,
+                } if aura_direction == direction && aura_value_type == value_type => {
+                    Some(amount * aura.stacks as i32)
+                }
+                _ => None,
+            })
+            .sum()
+    }
+
+    /// Sum of the flat and of the percentage damage changes in one direction.
+    pub fn damage_modifiers(&self, direction: DamageChangeDirection) -> DamageModifiers {
+        DamageModifiers {
+            flat: self.damage_change(direction, DamageChangeType::Amount),
+            percent: self.damage_change(direction, DamageChangeType::Percent),
+        }
     }
 }
 
-/// MOVEMENT EFFECT
-struct _MovementEffect {
-    decrease_increase_flag: bool, // true -> increase, false -> decrease,
-    percentage_change: u8, // value between 1-100 that determines how fast/slow this effect makes the target
+/// Sent whenever an aura is applied, including when it is refreshed, extended or stacked.
+#[derive(Event)]
+pub struct AuraApplied {
+    pub target: Entity,
+    pub spell_id: SpellId,
+    pub source: Option<Entity>,
+    pub stacks: u32,
 }
 
-/// DAMAGE INCREMENT/DECREMENT
-struct _DamageChange {
-    amount: u32,
-    value_type: _DamageChangeType,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuraRemovedReason {
+    Expired,
+    /// A shield was used up.
+    Depleted,
+    Dispelled,
+    /// Whoever had the aura died.
+    Died,
 }
 
-enum _DamageChangeType {
-    Percent,
-    Amount,
+/// Sent whenever an aura runs out, is used up, is dispelled or its holder dies.
+#[derive(Event)]
+pub struct AuraRemoved {
+    pub target: Entity,
+    pub spell_id: SpellId,
+    pub reason: AuraRemovedReason,
+}
+
+/// Applies the aura to `entity`.
+pub fn apply_aura(
+    entity: Entity,
+    instance: AuraInstance,
+    auras: &mut Auras,
+    aura_applied_events: &mut EventWriter<AuraApplied>,
+) {
+    let applied = auras.apply(instance).map(|applied| AuraApplied {
+        target: entity,
+        spell_id: applied.spell_id.clone(),
+        source: applied.source,
+        stacks: applied.stacks,
+    });
+
+    match applied {
+        Some(applied) => aura_applied_events.send(applied),
+        None => debug!("{:?} is immune", entity),
+    }
 }
 
-// fn emit_auras() {}
+/// Ticks every aura, applying damage and healing over time, pulsing area auras
+/// and removing auras that ran out, were used up or were dispelled.
+fn aura_system(
+    mut entities: Query<(Entity, &mut Auras)>,
+    time: Res<Time>,
+    mut aura_removed_events: EventWriter<AuraRemoved>,
+    mut aura_pulsed_events: EventWriter<AuraPulsed>,
+    mut damage_requests: EventWriter<DamageRequest>,
+    mut heal_requests: EventWriter<HealRequest>,
+) {
+    // looked up up front, since casters are in the same query as the units they put auras on
+    let casters: HashMap<Entity, CasterModifiers> = entities
+        .iter()
+        .map(|(entity, auras)| (entity, auras.caster_modifiers()))
+        .collect();
+
+    for (entity, mut auras) in entities.iter_mut() {
+        auras.diminishing_returns.tick(time.delta());
+
+        for aura in auras.applied.iter_mut() {
+            // auras taken off early don't get another tick in before they are removed
+            if aura.removed.is_some() {
+                continue;
+            }
+
+            if let Some(caster) = aura.source.and_then(|source| casters.get(&source)) {
+                aura.update_caster(*caster);
+            }
+
+            let remaining = aura.duration.remaining();
+
+            aura.duration.tick(time.delta());
+
+            let Some(ticks) = aura.ticks.as_mut() else {
+                continue;
+            };
+
+            let fractions = ticks.advance(time.delta(), remaining);
+
+            match &aura.effect {
+                AuraEffect::Overtime {
+                    amount, healing, ..
+                } => {
+                    for fraction in fractions {
+                        let (amount, remainder) = whole_amount(
+                            *amount as f32 * aura.stacks as f32 * fraction + aura.remainder,
+                        );
+
+                        aura.remainder = remainder;
+
+                        if amount == 0 {
+                            continue;
+                        }
+
+                        if *healing {
+                            heal_requests.send(HealRequest::new(
+                                aura.source,
+                                entity,
+                                &aura.spell_id,
+                                amount,
+                            ));
+                            continue;
+                        }
+
+                        let request = DamageRequest::new(
+                            aura.source,
+                            entity,
+                            &aura.spell_id,
+                            aura.school,
+                            amount,
+                        )
+                        .periodic();
+
+                        damage_requests.send(match aura.snapshot {
+                            Some(snapshot) => request.with_snapshot(snapshot.damage_dealt),
+                            None => request,
+                        });
+                    }
+                }
+                AuraEffect::Pulse {
+                    radius,
+                    targets,
+                    effects,
+                    ..
+                } => {
+                    // a pulse happens or it doesn't, a final tick cut short doesn't count
+                    for _ in fractions.iter().filter(|fraction| **fraction >= 1.0) {
+                        aura_pulsed_events.send(AuraPulsed {
+                            holder: entity,
+                            source: aura.source,
+                            spell_id: aura.spell_id.clone(),
+                            school: aura.school,
+                            radius: *radius,
+                            targets: *targets,
+                            effects: effects.clone(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        auras.applied.retain(|aura| {
+            let Some(reason) = aura.removal_reason() else {
+                return true;
+            };
+
+            aura_removed_events.send(AuraRemoved {
+                target: entity,
+                spell_id: aura.spell_id.clone(),
+                reason,
+            });
+
+            false
+        });
+    }
+}
 
 pub struct AurasPlugin;
 
 impl Plugin for AurasPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        // app.add_system(emit_auras);
-        app.add_systems(Update, overtime_system);
+        app.add_event::<AuraApplied>();
+        app.add_event::<AuraRemoved>();
+        app.add_event::<AuraPulsed>();
+        app.add_systems(
+            Update,
+            (
+                aura_system,
+                fear_system,
+                aura_pulse_system,
+                pulse_gizmo_system,
+            ),
+        );
     }
 }
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
 use bevy::ui::camera_config::UiCameraConfig;
 // use bevy_rapier3d::prelude::*;
@@ -7,10 +9,18 @@ use bevy_xpbd_3d::{
 };
 
 use crate::{
+    auras::{Aura, AuraEffect, AuraScaling, Auras, DispelType, RefreshPolicy},
     controller::CharacterControllerBundle,
+    death::Respawn,
     health::Health,
     health_bars::PrimaryCamera,
+    hit_table::CombatStats,
     orbit_camera::{self},
+    resource_pool::{ResourcePool, ResourceType},
+    spells::{
+        Cooldowns, CurrentTarget, SpellEffect, SpellSchool, TriggerDefinition, TriggerEvent,
+        Triggers,
+    },
 };
 
 // use crate::{interaction_flags, resource};
@@ -97,6 +107,21 @@ pub fn create_character_controller(
             max: 100,
         },
         Player {},
+        Name::new("Player"),
+        Cooldowns::default(),
+        CurrentTarget::default(),
+        Auras::default(),
+        Respawn {
+            point: transform.translation,
+            health: 0.5,
+        },
+        CombatStats {
+            hit: 0.02,
+            crit: 0.1,
+            ..Default::default()
+        },
+        player_triggers(),
+        ResourcePool::new(ResourceType::Mana, 100),
         CharacterControllerBundle::new(Collider::capsule(1.0, 0.4), Vector::NEG_Y * 9.81 * 2.0)
             .with_movement(100.0, 0.92, 7.0, (30.0 as Scalar).to_radians()),
         CharacterDirection {
@@ -222,3 +247,40 @@ pub fn update_character_transform(
         transform.look_to(direction.forward, Vec3::Y);
     }
 }
+
+fn player_triggers() -> Triggers {
+    Triggers::new(vec![
+        TriggerDefinition::new(
+            "Shadow Embrace",
+            TriggerEvent::Hit,
+            vec![SpellEffect::ApplyAura(Aura {
+                effect: AuraEffect::Overtime {
+                    amount: 2,
+                    healing: false,
+                    every: Duration::from_secs(1),
+                },
+                duration: Duration::from_secs(4),
+                max_stacks: 3,
+                refresh: RefreshPolicy::Stack,
+                dispel: DispelType::Curse,
+                harmful: true,
+                scaling: AuraScaling::Dynamic,
+            })],
+        )
+        .with_chance(0.1)
+        .with_internal_cooldown(Duration::from_secs(3))
+        .with_school(SpellSchool::Shadow)
+        .targeting_other(),
+        TriggerDefinition::new(
+            "Presence of Mind",
+            TriggerEvent::Cast,
+            vec![SpellEffect::NextCastInstant],
+        )
+        .every(3),
+        TriggerDefinition::new(
+            "Vampiric Strikes",
+            TriggerEvent::Crit,
+            vec![SpellEffect::Heal { amount: 5 }],
+        ),
+    ])
+}
@@ -0,0 +1,334 @@
+use std::{collections::VecDeque, fmt::Write as _, fs, time::Duration};
+
+use bevy::prelude::*;
+
+use crate::{
+    auras::{AuraApplied, AuraRemoved},
+    damage::{DamageDealt, HealApplied},
+    death::{Died, Resurrected},
+    hit_table::HitOutcome,
+    spells::{CastFailed, CastInterrupted, CastSpellFire, Casting, SpellId},
+};
+
+/// How many entries the combat log keeps before dropping the oldest.
+const COMBAT_LOG_CAPACITY: usize = 1000;
+
+/// File the combat log is exported to, relative to the working directory.
+const COMBAT_LOG_EXPORT_PATH: &str = "combat_log.txt";
+
+/// What a combat log entry is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CombatLogKind {
+    Cast,
+    Damage,
+    Heal,
+    Aura,
+    Death,
+}
+
+pub struct CombatLogEntry {
+    /// Time since startup.
+    pub at: Duration,
+    pub kind: CombatLogKind,
+    /// What happened, with entities already resolved to their names, since they might not
+    /// be around anymore by the time the entry is read.
+    pub message: String,
+}
+
+impl CombatLogEntry {
+    /// The entry as a single line, e.g. for exporting.
+    pub fn line(&self) -> String {
+        format!(
+            "[{:>9.3}] {:<6} {}",
+            self.at.as_secs_f32(),
+            format!("{:?}", self.kind).to_uppercase(),
+            self.message
+        )
+    }
+}
+
+/// Everything that happened in combat recently, oldest first.
+/// Once full, every new entry pushes out the oldest one.
+#[derive(Resource)]
+pub struct CombatLog {
+    capacity: usize,
+    entries: VecDeque<CombatLogEntry>,
+}
+
+impl CombatLog {
+    pub fn new(capacity: usize) -> Self {
+        CombatLog {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn record(&mut self, at: Duration, kind: CombatLogKind, message: String) {
+        debug!("{:?}: {}", kind, message);
+
+        if self.capacity == 0 {
+            return;
+        }
+
+        while self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+
+        self.entries.push_back(CombatLogEntry { at, kind, message });
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &CombatLogEntry> + ExactSizeIterator {
+        self.entries.iter()
+    }
+
+    /// The whole log, one entry per line.
+    pub fn export(&self) -> String {
+        self.entries.iter().fold(String::new(), |mut text, entry| {
+            let _ = writeln!(text, "{}", entry.line());
+            text
+        })
+    }
+}
+
+impl Default for CombatLog {
+    fn default() -> Self {
+        CombatLog::new(COMBAT_LOG_CAPACITY)
+    }
+}
+
+/// The entity's name if it has one, its id otherwise.
+fn name_of(names: &Query<&Name>, entity: Entity) -> String {
+    names
+        .get(entity)
+        .map_or_else(|_| format!("{:?}", entity), |name| name.to_string())
+}
+
+fn source_name(names: &Query<&Name>, source: Option<Entity>) -> String {
+    source.map_or_else(
+        || "Environment".to_string(),
+        |source| name_of(names, source),
+    )
+}
+
+fn spell_name(spell: &Option<SpellId>) -> &str {
+    spell.as_ref().map_or("melee", |spell| spell.0.as_str())
+}
+
+fn record_cast_system(
+    time: Res<Time>,
+    mut log: ResMut<CombatLog>,
+    names: Query<&Name>,
+    started: Query<(Entity, &Casting), Added<Casting>>,
+    mut cast_spell_fire_events: EventReader<CastSpellFire>,
+    mut cast_failed_events: EventReader<CastFailed>,
+    mut cast_interrupted_events: EventReader<CastInterrupted>,
+) {
+    let at = time.elapsed();
+
+    for (caster, casting) in &started {
+        log.record(
+            at,
+            CombatLogKind::Cast,
+            format!(
+                "{} begins casting {}",
+                name_of(&names, caster),
+                casting.spell_id.0
+            ),
+        );
+    }
+
+    for event in cast_spell_fire_events.read() {
+        // a channel's ticks would drown out everything else
+        if event.channel_tick {
+            continue;
+        }
+
+        log.record(
+            at,
+            CombatLogKind::Cast,
+            format!("{} casts {}", name_of(&names, event.caster), event.id.0),
+        );
+    }
+
+    for event in cast_failed_events.read() {
+        log.record(
+            at,
+            CombatLogKind::Cast,
+            format!(
+                "{} fails to cast {}: {:?}",
+                name_of(&names, event.caster),
+                event.spell_id.0,
+                event.reason
+            ),
+        );
+    }
+
+    for event in cast_interrupted_events.read() {
+        log.record(
+            at,
+            CombatLogKind::Cast,
+            format!(
+                "{}'s {} is interrupted: {:?}",
+                name_of(&names, event.caster),
+                event.spell_id.0,
+                event.reason
+            ),
+        );
+    }
+}
+
+fn record_combat_system(
+    time: Res<Time>,
+    mut log: ResMut<CombatLog>,
+    names: Query<&Name>,
+    mut damage_dealt_events: EventReader<DamageDealt>,
+    mut heal_applied_events: EventReader<HealApplied>,
+    mut died_events: EventReader<Died>,
+    mut resurrected_events: EventReader<Resurrected>,
+) {
+    let at = time.elapsed();
+
+    for event in damage_dealt_events.read() {
+        let message = match event.outcome {
+            HitOutcome::Miss | HitOutcome::Dodge | HitOutcome::Parry => format!(
+                "{}'s {} on {}: {:?}",
+                source_name(&names, event.source),
+                spell_name(&event.spell),
+                name_of(&names, event.target),
+                event.outcome
+            ),
+            outcome => format!(
+                "{}'s {} {} {} for {} {:?} (overkill {}, blocked {}, resisted {}, absorbed {})",
+                source_name(&names, event.source),
+                spell_name(&event.spell),
+                if outcome == HitOutcome::Crit {
+                    "crits"
+                } else {
+                    "hits"
+                },
+                name_of(&names, event.target),
+                event.amount,
+                event.school,
+                event.overkill,
+                event.blocked,
+                event.resisted,
+                event.absorbed
+            ),
+        };
+
+        log.record(at, CombatLogKind::Damage, message);
+    }
+
+    for event in heal_applied_events.read() {
+        log.record(
+            at,
+            CombatLogKind::Heal,
+            format!(
+                "{}'s {} heals {} for {} (overheal {})",
+                source_name(&names, event.source),
+                spell_name(&event.spell),
+                name_of(&names, event.target),
+                event.amount,
+                event.overheal
+            ),
+        );
+    }
+
+    for event in died_events.read() {
+        let message = match event.killer {
+            Some(killer) => format!(
+                "{} is killed by {}",
+                name_of(&names, event.entity),
+                name_of(&names, killer)
+            ),
+            None => format!("{} dies", name_of(&names, event.entity)),
+        };
+
+        log.record(at, CombatLogKind::Death, message);
+    }
+
+    for event in resurrected_events.read() {
+        log.record(
+            at,
+            CombatLogKind::Death,
+            format!(
+                "{} is resurrected with {} health",
+                name_of(&names, event.entity),
+                event.health
+            ),
+        );
+    }
+}
+
+fn record_aura_system(
+    time: Res<Time>,
+    mut log: ResMut<CombatLog>,
+    names: Query<&Name>,
+    mut aura_applied_events: EventReader<AuraApplied>,
+    mut aura_removed_events: EventReader<AuraRemoved>,
+) {
+    let at = time.elapsed();
+
+    for event in aura_applied_events.read() {
+        log.record(
+            at,
+            CombatLogKind::Aura,
+            format!(
+                "{} gains {} ({}) from {}",
+                name_of(&names, event.target),
+                event.spell_id.0,
+                event.stacks,
+                source_name(&names, event.source)
+            ),
+        );
+    }
+
+    for event in aura_removed_events.read() {
+        log.record(
+            at,
+            CombatLogKind::Aura,
+            format!(
+                "{} loses {}: {:?}",
+                name_of(&names, event.target),
+                event.spell_id.0,
+                event.reason
+            ),
+        );
+    }
+}
+
+/// Writes the combat log to a file when F9 is pressed.
+fn export_combat_log_system(keyboard_input: Res<Input<KeyCode>>, log: Res<CombatLog>) {
+    if !keyboard_input.just_pressed(KeyCode::F9) {
+        return;
+    }
+
+    match fs::write(COMBAT_LOG_EXPORT_PATH, log.export()) {
+        Ok(()) => info!(
+            "Exported {} combat log entries to {}",
+            log.iter().len(),
+            COMBAT_LOG_EXPORT_PATH
+        ),
+        Err(error) => warn!(
+            "Failed to export the combat log to {}: {}",
+            COMBAT_LOG_EXPORT_PATH, error
+        ),
+    }
+}
+
+pub struct CombatLogPlugin;
+
+impl Plugin for CombatLogPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CombatLog>();
+        app.add_systems(
+            Update,
+            (
+                record_cast_system,
+                record_combat_system,
+                record_aura_system,
+                export_combat_log_system,
+            ),
+        );
+    }
+}
@@ -1,4 +1,7 @@
-use crate::character_controller::CharacterDirection;
+use crate::{
+    auras::Auras, character_controller::CharacterDirection, crowd_control::CrowdControl,
+    death::Dead,
+};
 use bevy::{ecs::query::Has, prelude::*};
 use bevy_xpbd_3d::{math::*, prelude::*, SubstepSchedule, SubstepSet};
 pub struct CharacterControllerPlugin;
@@ -242,31 +245,55 @@ fn update_grounded(
 }
 
 /// Responds to [`MovementAction`] events and moves character controllers accordingly.
+/// Acceleration is scaled by any slows or hastes the character is affected by,
+/// and crowd control can keep the character from moving or jumping at all.
 fn movement(
     time: Res<Time>,
     mut movement_event_reader: EventReader<MovementAction>,
-    mut controllers: Query<(
-        &MovementAcceleration,
-        &JumpImpulse,
-        &mut LinearVelocity,
-        Has<Grounded>,
-    )>,
+    mut controllers: Query<
+        (
+            &MovementAcceleration,
+            &JumpImpulse,
+            &mut LinearVelocity,
+            Has<Grounded>,
+            Option<&Auras>,
+        ),
+        Without<Dead>,
+    >,
 ) {
     // Precision is adjusted so that the example works with
     // both the `f32` and `f64` features. Otherwise you don't need this.
     let delta_time = time.delta_seconds_f64().adjust_precision();
 
     for event in movement_event_reader.read() {
-        for (movement_acceleration, jump_impulse, mut linear_velocity, is_grounded) in
+        for (movement_acceleration, jump_impulse, mut linear_velocity, is_grounded, auras) in
             &mut controllers
         {
             match event {
                 MovementAction::Move(direction) => {
-                    linear_velocity.x += direction.x * movement_acceleration.0 * delta_time;
-                    linear_velocity.z += direction.y * movement_acceleration.0 * delta_time;
+                    if auras.is_some_and(|auras| {
+                        auras
+                            .crowd_controlled_by(CrowdControl::blocks_movement)
+                            .is_some()
+                    }) {
+                        continue;
+                    }
+
+                    let speed_multiplier =
+                        auras.map_or(1.0, |auras| auras.speed_multiplier()) as Scalar;
+                    let acceleration = movement_acceleration.0 * speed_multiplier;
+
+                    linear_velocity.x += direction.x * acceleration * delta_time;
+                    linear_velocity.z += direction.y * acceleration * delta_time;
                 }
                 MovementAction::Jump => {
-                    if is_grounded {
+                    let jump_blocked = auras.is_some_and(|auras| {
+                        auras
+                            .crowd_controlled_by(CrowdControl::blocks_jumping)
+                            .is_some()
+                    });
+
+                    if is_grounded && !jump_blocked {
                         linear_velocity.y = jump_impulse.0;
                     }
                 }
@@ -0,0 +1,226 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_xpbd_3d::prelude::LinearVelocity;
+use serde::Deserialize;
+
+use crate::{auras::Auras, death::Dead, rng::CombatRng};
+
+/// How long after the last application of a crowd control that the next one is diminished.
+pub const DIMINISHING_RETURNS_WINDOW: Duration = Duration::from_secs(18);
+
+/// Duration multipliers for the 1st, 2nd and 3rd application within the window,
+/// after which the target is immune until the window runs out.
+const DIMINISHING_RETURNS: [f32; 3] = [1.0, 0.5, 0.25];
+
+/// Speed at which feared units run.
+const FEAR_SPEED: f32 = 4.0;
+
+/// How far (in degrees, either way) a fear can stray from running straight away from its source.
+const FEAR_SPREAD: f32 = 45.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum CrowdControl {
+    /// Blocks movement and casting.
+    Stun,
+    /// Blocks movement only.
+    Root,
+    /// Blocks casting only.
+    Silence,
+    /// Blocks casting, and makes the unit run away from whoever feared it.
+    Fear,
+}
+
+impl CrowdControl {
+    pub fn blocks_movement(&self) -> bool {
+        matches!(
+            self,
+            CrowdControl::Stun | CrowdControl::Root | CrowdControl::Fear
+        )
+    }
+
+    /// Roots still allow jumping in place.
+    pub fn blocks_jumping(&self) -> bool {
+        matches!(self, CrowdControl::Stun | CrowdControl::Fear)
+    }
+
+    /// Whether a feared unit is kept from running.
+    pub fn holds_in_place(&self) -> bool {
+        matches!(self, CrowdControl::Stun | CrowdControl::Root)
+    }
+
+    pub fn blocks_casting(&self) -> bool {
+        matches!(
+            self,
+            CrowdControl::Stun | CrowdControl::Silence | CrowdControl::Fear
+        )
+    }
+}
+
+/// Tracks how often each kind of crowd control has been applied to a unit recently.
+#[derive(Default)]
+pub struct DiminishingReturns {
+    applied: HashMap<CrowdControl, (usize, Timer)>,
+}
+
+impl DiminishingReturns {
+    /// Records another application of `crowd_control` and returns the multiplier for its duration,
+    /// or `None` if the unit is immune to it for now.
+    pub fn apply(&mut self, crowd_control: CrowdControl) -> Option<f32> {
+        let (count, window) = self
+            .applied
+            .entry(crowd_control)
+            .or_insert_with(|| (0, Timer::new(DIMINISHING_RETURNS_WINDOW, TimerMode::Once)));
+
+        let multiplier = DIMINISHING_RETURNS.get(*count).copied();
+
+        // immunity doesn't extend the window, only actual applications do
+        if multiplier.is_some() {
+            *count += 1;
+            window.reset();
+        }
+
+        multiplier
+    }
+
+    pub fn tick(&mut self, delta: Duration) {
+        self.applied
+            .retain(|_, (_, window)| !window.tick(delta).finished());
+    }
+}
+
+/// Direction a feared unit is running in.
+#[derive(Component)]
+pub struct Fleeing {
+    direction: Vec3,
+}
+
+/// Starts feared units running away from the source of their fear, and stops them once it ends.
+pub fn fear_system(
+    mut commands: Commands,
+    mut rng: ResMut<CombatRng>,
+    mut feared: Query<
+        (
+            Entity,
+            &Transform,
+            &Auras,
+            Option<&Fleeing>,
+            Option<&mut LinearVelocity>,
+        ),
+        Without<Dead>,
+    >,
+    sources: Query<&Transform>,
+) {
+    for (entity, transform, auras, fleeing, velocity) in &mut feared {
+        let fear = auras.fear();
+
+        let direction = match (fear, fleeing) {
+            (Some(fear), None) => {
+                let away = fear
+                    .source
+                    .and_then(|source| sources.get(source).ok())
+                    .map(|source| transform.translation - source.translation)
+                    .unwrap_or(transform.back());
+                let away = Vec3::new(away.x, 0.0, away.z).normalize_or_zero();
+
+                let spread = (rng.range(-FEAR_SPREAD, FEAR_SPREAD)).to_radians();
+                let direction = Quat::from_rotation_y(spread) * away;
+
+                commands.entity(entity).insert(Fleeing { direction });
+
+                direction
+            }
+            (Some(_), Some(fleeing)) => fleeing.direction,
+            (None, Some(_)) => {
+                commands.entity(entity).remove::<Fleeing>();
+
+                Vec3::ZERO
+            }
+            (None, None) => continue,
+        };
+
+        // stuns and roots keep a feared unit in place
+        let speed = if auras
+            .crowd_controlled_by(CrowdControl::holds_in_place)
+            .is_some()
+        {
+            0.0
+        } else {
+            FEAR_SPEED * auras.speed_multiplier()
+        };
+
+        if let Some(mut velocity) = velocity {
+            velocity.x = direction.x * speed;
+            velocity.z = direction.z * speed;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn applications(
+        diminishing_returns: &mut DiminishingReturns,
+        times: usize,
+    ) -> Vec<Option<f32>> {
+        (0..times)
+            .map(|_| diminishing_returns.apply(CrowdControl::Stun))
+            .collect()
+    }
+
+    #[test]
+    fn halves_each_time_then_immune() {
+        let mut diminishing_returns = DiminishingReturns::default();
+
+        assert_eq!(
+            applications(&mut diminishing_returns, 5),
+            vec![Some(1.0), Some(0.5), Some(0.25), None, None]
+        );
+    }
+
+    #[test]
+    fn resets_once_the_window_runs_out() {
+        let mut diminishing_returns = DiminishingReturns::default();
+        applications(&mut diminishing_returns, 4);
+
+        diminishing_returns.tick(DIMINISHING_RETURNS_WINDOW - Duration::from_millis(1));
+        assert_eq!(diminishing_returns.apply(CrowdControl::Stun), None);
+
+        diminishing_returns.tick(Duration::from_millis(1));
+        assert_eq!(diminishing_returns.apply(CrowdControl::Stun), Some(1.0));
+    }
+
+    #[test]
+    fn every_application_restarts_the_window() {
+        let mut diminishing_returns = DiminishingReturns::default();
+        let most_of_the_window = DIMINISHING_RETURNS_WINDOW - Duration::from_secs(1);
+
+        assert_eq!(diminishing_returns.apply(CrowdControl::Stun), Some(1.0));
+        diminishing_returns.tick(most_of_the_window);
+        assert_eq!(diminishing_returns.apply(CrowdControl::Stun), Some(0.5));
+        diminishing_returns.tick(most_of_the_window);
+        assert_eq!(diminishing_returns.apply(CrowdControl::Stun), Some(0.25));
+    }
+
+    #[test]
+    fn immunity_does_not_extend_the_window() {
+        let mut diminishing_returns = DiminishingReturns::default();
+        applications(&mut diminishing_returns, 3);
+
+        diminishing_returns.tick(Duration::from_secs(10));
+        assert_eq!(diminishing_returns.apply(CrowdControl::Stun), None);
+
+        diminishing_returns.tick(DIMINISHING_RETURNS_WINDOW - Duration::from_secs(10));
+        assert_eq!(diminishing_returns.apply(CrowdControl::Stun), Some(1.0));
+    }
+
+    #[test]
+    fn each_kind_diminishes_on_its_own() {
+        let mut diminishing_returns = DiminishingReturns::default();
+        applications(&mut diminishing_returns, 3);
+
+        assert_eq!(diminishing_returns.apply(CrowdControl::Root), Some(1.0));
+        assert_eq!(diminishing_returns.apply(CrowdControl::Stun), None);
+    }
+}
@@ -1,24 +1,312 @@
 use std::cmp::min;
 
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
 
-use crate::{damage_text::spawn_damage_text_on_entity, health::Health};
+use crate::{
+    auras::{Auras, DamageChangeDirection},
+    death::Dead,
+    health::Health,
+    hit_table::{roll_hit, CombatStats, HitKind, HitOutcome},
+    rng::CombatRng,
+    spells::{SpellId, SpellSchool},
+};
+
+/// Resistance at which half of the damage of a school is mitigated. Armor against physical
+/// damage takes a lot more to get there than resistances against magic.
+const ARMOR_HALF_MITIGATION: f32 = 400.0;
+const RESISTANCE_HALF_MITIGATION: f32 = 100.0;
 
 #[derive(Component)]
 pub struct Damage {
     pub amount: u32,
+    pub school: SpellSchool,
+}
+
+/// Asks for damage to be dealt. Every bit of damage goes through here and is resolved by the
+/// [`damage_resolution_system`], so that modifiers and shields always apply.
+#[derive(Event, Clone)]
+pub struct DamageRequest {
+    /// Whoever deals the damage, if anyone.
+    pub source: Option<Entity>,
+    pub target: Entity,
+    /// The spell (or trigger) the damage comes from.
+    pub spell: Option<SpellId>,
+    pub school: SpellSchool,
+    pub amount: u32,
+    /// The source's damage dealt modifiers from some earlier moment, e.g. when a snapshotting
+    /// damage over time aura was applied. Looked up when the request is resolved otherwise.
+    pub snapshot: Option<DamageModifiers>,
+    /// Ticks of damage over time can't be missed or avoided.
+    pub periodic: bool,
+}
+
+impl DamageRequest {
+    pub fn new(
+        source: Option<Entity>,
+        target: Entity,
+        spell: &SpellId,
+        school: SpellSchool,
+        amount: u32,
+    ) -> Self {
+        DamageRequest {
+            source,
+            target,
+            spell: Some(spell.clone()),
+            school,
+            amount,
+            snapshot: None,
+            periodic: false,
+        }
+    }
+
+    pub fn periodic(mut self) -> Self {
+        self.periodic = true;
+        self
+    }
+
+    pub fn with_snapshot(mut self, dealt: DamageModifiers) -> Self {
+        self.snapshot = Some(dealt);
+        self
+    }
+}
+
+/// Sent for every resolved [`DamageRequest`]. Combat text, triggers, pushback and
+/// logging all hang off this one event.
+#[derive(Event, Clone)]
+pub struct DamageDealt {
+    pub source: Option<Entity>,
+    pub target: Entity,
+    pub spell: Option<SpellId>,
+    /// Damage that went through to health, overkill included.
+    pub amount: u32,
+    pub school: SpellSchool,
+    /// Misses, dodges and parries deal no damage at all.
+    pub outcome: HitOutcome,
+    /// How much of `amount` was more than the target had health left.
+    pub overkill: u32,
+    /// How much a block took off.
+    pub blocked: u32,
+    /// How much the target's armor or resistance mitigated.
+    pub resisted: u32,
+    /// How much shields soaked up before the rest went through.
+    pub absorbed: u32,
+    /// Whether this was a tick of damage over time.
+    pub periodic: bool,
+}
+
+/// Asks for healing to be done, resolved by the [`heal_resolution_system`].
+#[derive(Event, Clone)]
+pub struct HealRequest {
+    pub source: Option<Entity>,
+    pub target: Entity,
+    pub spell: Option<SpellId>,
+    pub amount: u32,
+}
+
+impl HealRequest {
+    pub fn new(source: Option<Entity>, target: Entity, spell: &SpellId, amount: u32) -> Self {
+        HealRequest {
+            source,
+            target,
+            spell: Some(spell.clone()),
+            amount,
+        }
+    }
+}
+
+/// Sent for every resolved [`HealRequest`].
+#[derive(Event, Clone)]
+pub struct HealApplied {
+    pub source: Option<Entity>,
+    pub target: Entity,
+    pub spell: Option<SpellId>,
+    /// Healing that went through, overhealing included.
+    pub amount: u32,
+    /// How much of `amount` was more than the target was missing.
+    pub overheal: u32,
 }
 
-pub fn apply_damage(commands: &mut Commands, entity: Entity, amount: u32, health: &mut Health) {
-    spawn_damage_text_on_entity(commands, entity, amount);
+/// The damage changes from every aura on a unit in one direction, summed up.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DamageModifiers {
+    pub flat: i32,
+    pub percent: i32,
+}
+
+impl DamageModifiers {
+    /// Adds the flat amount before applying the percentage.
+    fn apply(&self, amount: f32) -> f32 {
+        let amount = (amount + self.flat as f32).max(0.0);
+
+        amount * (1.0 + self.percent as f32 / 100.0).max(0.0)
+    }
+}
+
+/// Armor against physical damage and resistances against every other school.
+/// Negative values make for weaknesses, taking more damage from the school instead of less.
+#[derive(Component, Debug, Clone, Default)]
+pub struct Resistances {
+    pub armor: i32,
+    pub schools: HashMap<SpellSchool, i32>,
+}
 
-    health.current = health.current - min(amount, health.current);
+impl Resistances {
+    pub fn with_armor(mut self, armor: i32) -> Self {
+        self.armor = armor;
+        self
+    }
+
+    pub fn with_resistance(mut self, school: SpellSchool, resistance: i32) -> Self {
+        self.schools.insert(school, resistance);
+        self
+    }
+
+    /// Fraction of damage of `school` that is mitigated, `r / (|r| + k)` where `k` is the
+    /// resistance at which half the damage is mitigated. Approaches 1 for high resistances,
+    /// and -1 (double damage) for big weaknesses.
+    pub fn mitigation(&self, school: SpellSchool) -> f32 {
+        let (resistance, half_mitigation) = match school {
+            SpellSchool::Physical => (self.armor, ARMOR_HALF_MITIGATION),
+            school => (
+                self.schools.get(&school).copied().unwrap_or_default(),
+                RESISTANCE_HALF_MITIGATION,
+            ),
+        };
+
+        let resistance = resistance as f32;
+
+        resistance / (resistance.abs() + half_mitigation)
+    }
+
+    /// Returns what's left of `amount` of damage of `school`.
+    fn mitigate(&self, amount: u32, school: SpellSchool) -> u32 {
+        (amount as f32 * (1.0 - self.mitigation(school))).round() as u32
+    }
+}
+
+/// Applies the source's damage dealt modifiers and then the target's damage taken modifiers.
+pub fn modified_damage(amount: u32, dealt: DamageModifiers, taken: DamageModifiers) -> u32 {
+    taken.apply(dealt.apply(amount as f32)).round() as u32
+}
+
+/// Resolves every [`DamageRequest`]: the hit table is rolled, damage dealt and taken modifiers
+/// apply, the target's armor or resistance mitigates, shields on the target soak up what they
+/// can, and the rest comes off its health. Corpses can't be damaged any further.
+pub fn damage_resolution_system(
+    mut damage_requests: EventReader<DamageRequest>,
+    mut damage_dealt_events: EventWriter<DamageDealt>,
+    mut rng: ResMut<CombatRng>,
+    mut units: Query<(
+        Option<&mut Health>,
+        Option<&mut Auras>,
+        Option<&Resistances>,
+        Option<&CombatStats>,
+        Has<Dead>,
+    )>,
+) {
+    for request in damage_requests.read() {
+        let source = request.source.and_then(|source| units.get(source).ok());
+
+        let dealt = request.snapshot.unwrap_or_else(|| {
+            source
+                .as_ref()
+                .and_then(|(_, auras, ..)| auras.as_deref())
+                .map(|auras| auras.damage_modifiers(DamageChangeDirection::Dealt))
+                .unwrap_or_default()
+        });
+        let attacker = source
+            .and_then(|(.., stats, _)| stats.copied())
+            .unwrap_or_default();
+
+        let Ok((Some(mut health), mut auras, resistances, defender, false)) =
+            units.get_mut(request.target)
+        else {
+            continue;
+        };
+
+        let outcome = roll_hit(
+            &mut rng,
+            HitKind::of(request.school, request.periodic),
+            attacker,
+            defender.copied().unwrap_or_default(),
+        );
+
+        let taken = auras
+            .as_deref()
+            .map(|auras| auras.damage_modifiers(DamageChangeDirection::Taken))
+            .unwrap_or_default();
+        let amount = modified_damage(request.amount, dealt, taken);
+
+        let blocked = match outcome {
+            HitOutcome::Block => amount - outcome.apply(amount),
+            _ => 0,
+        };
+        let amount = outcome.apply(amount);
+
+        let mitigated = resistances.map_or(amount, |resistances| {
+            resistances.mitigate(amount, request.school)
+        });
+        let resisted = amount.saturating_sub(mitigated);
+        let amount = mitigated;
+
+        let absorbed = auras.as_deref_mut().map_or(0, |auras| auras.absorb(amount));
+        let amount = amount - absorbed;
+
+        let overkill = amount - min(amount, health.current);
+        health.current -= amount - overkill;
+
+        damage_dealt_events.send(DamageDealt {
+            source: request.source,
+            target: request.target,
+            spell: request.spell.clone(),
+            amount,
+            school: request.school,
+            outcome,
+            overkill,
+            blocked,
+            resisted,
+            absorbed,
+            periodic: request.periodic,
+        });
+    }
+}
+
+/// Resolves every [`HealRequest`], healing the target up to its max health.
+/// The dead can't be healed, only resurrected.
+pub fn heal_resolution_system(
+    mut heal_requests: EventReader<HealRequest>,
+    mut heal_applied_events: EventWriter<HealApplied>,
+    mut units: Query<&mut Health, Without<Dead>>,
+) {
+    for request in heal_requests.read() {
+        let Ok(mut health) = units.get_mut(request.target) else {
+            continue;
+        };
+
+        let missing = health.max - min(health.current, health.max);
+        let overheal = request.amount - min(request.amount, missing);
+
+        health.current += request.amount - overheal;
+
+        heal_applied_events.send(HealApplied {
+            source: request.source,
+            target: request.target,
+            spell: request.spell.clone(),
+            amount: request.amount,
+            overheal,
+        });
+    }
 }
 
-pub fn apply_health(commands: &mut Commands, entity: Entity, amount: u32, health: &mut Health) {
-    spawn_damage_text_on_entity(commands, entity, amount);
+pub struct DamagePlugin;
 
-    if health.current < health.max {
-        health.current = min(health.current + amount, health.max);
+impl Plugin for DamagePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamageRequest>();
+        app.add_event::<DamageDealt>();
+        app.add_event::<HealRequest>();
+        app.add_event::<HealApplied>();
+        app.init_resource::<CombatRng>();
+        app.add_systems(Update, (damage_resolution_system, heal_resolution_system));
     }
 }
@@ -1,21 +1,15 @@
 use bevy::prelude::*;
 
 use crate::{
+    damage::{DamageDealt, HealApplied},
     health_bars::{
         convert_ndc_to_percentage_values, get_sceen_transform_and_visibility, PrimaryCamera,
     },
+    hit_table::HitOutcome,
     orbit_camera::OrbitCamera,
+    spells::SpellSchool,
 };
 
-pub fn spawn_damage_text_on_entity(commands: &mut Commands, entity: Entity, value: u32) {
-    commands.entity(entity).insert(AppliedDamage { value });
-}
-
-#[derive(Component)]
-pub struct AppliedDamage {
-    pub value: u32,
-}
-
 #[derive(Component)]
 pub struct DamageTextAttach {
     pub(crate) attached_to: Entity,
@@ -25,6 +19,54 @@ pub struct DamageTextAttach {
 #[derive(Component)]
 pub struct DamageText {
     pub value: u32,
+    /// How much of the damage shields soaked up.
+    pub absorbed: u32,
+    pub outcome: HitOutcome,
+    pub color: Color,
+}
+
+/// Damage text is tinted by the school of the damage.
+fn school_color(school: SpellSchool) -> Color {
+    match school {
+        SpellSchool::Physical => Color::WHITE,
+        SpellSchool::Arcane => Color::FUCHSIA,
+        SpellSchool::Fire => Color::ORANGE_RED,
+        SpellSchool::Frost => Color::CYAN,
+        SpellSchool::Nature => Color::LIME_GREEN,
+        SpellSchool::Shadow => Color::PURPLE,
+        SpellSchool::Holy => Color::GOLD,
+    }
+}
+
+impl DamageText {
+    fn label(&self) -> String {
+        match self.outcome {
+            HitOutcome::Miss => return "MISS".to_string(),
+            HitOutcome::Dodge => return "DODGE".to_string(),
+            HitOutcome::Parry => return "PARRY".to_string(),
+            HitOutcome::Hit | HitOutcome::Crit | HitOutcome::Block => {}
+        }
+
+        let mut label = format!("{}", self.value);
+
+        if self.outcome == HitOutcome::Block {
+            label.push_str(" (blocked)");
+        }
+
+        if self.absorbed > 0 {
+            label.push_str(&format!(" ({} absorbed)", self.absorbed));
+        }
+
+        label
+    }
+
+    /// Crits stand out.
+    fn font_size(&self) -> f32 {
+        match self.outcome {
+            HitOutcome::Crit => 32.0,
+            _ => 20.0,
+        }
+    }
 }
 
 #[derive(Bundle)]
@@ -52,18 +94,15 @@ impl Plugin for DamageTextPlugin {
 }
 
 fn update_damage_text(
-    mut healthbars: Query<
-        (
-            Entity,
-            &mut Text,
-            &mut Style,
-            &DamageTextAttach,
-            &mut Visibility,
-            &DamageTextLifetime,
-            &DamageText,
-        ),
-        // Without<AppliedDamage>,
-    >,
+    mut healthbars: Query<(
+        Entity,
+        &mut Text,
+        &mut Style,
+        &DamageTextAttach,
+        &mut Visibility,
+        &DamageTextLifetime,
+        &DamageText,
+    )>,
     asset_server: Res<AssetServer>,
     entites: Query<&Transform>,
     camera_q: Query<(&Camera, &GlobalTransform), With<PrimaryCamera>>,
@@ -96,16 +135,15 @@ fn update_damage_text(
             hb_style.left = Val::Percent(x);
             // hb_style.position.top = Val::Percent(100.0 - y);
 
-            let current = text.value;
             let style = TextStyle {
-                font_size: 20.0,
-                color: Color::WHITE,
+                font_size: text.font_size(),
+                color: text.color,
                 font: asset_server.load("Rosela.ttf"),
             };
 
             *hb_text = Text {
                 sections: [TextSection {
-                    value: format!("{current}"),
+                    value: text.label(),
                     style,
                 }]
                 .to_vec(),
@@ -118,12 +156,42 @@ fn update_damage_text(
 fn spawn_damage_text_children(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    entities: Query<(Entity, &AppliedDamage, &Transform), Added<AppliedDamage>>,
+    mut damage_dealt_events: EventReader<DamageDealt>,
+    mut heal_applied_events: EventReader<HealApplied>,
+    entities: Query<&Transform>,
     camera_q: Query<(&Camera, &GlobalTransform), With<PrimaryCamera>>,
     orbit_camera: Query<&OrbitCamera>,
 ) {
-    for (entity, health, transform) in entities.iter() {
-        let current = health.value;
+    let damage = damage_dealt_events.read().map(|event| {
+        (
+            event.target,
+            DamageText {
+                value: event.amount,
+                absorbed: event.absorbed,
+                outcome: event.outcome,
+                color: school_color(event.school),
+            },
+        )
+    });
+    let healing = heal_applied_events.read().map(|event| {
+        (
+            event.target,
+            DamageText {
+                value: event.amount,
+                absorbed: 0,
+                outcome: HitOutcome::Hit,
+                color: Color::GREEN,
+            },
+        )
+    });
+
+    for (entity, damage_text) in damage.chain(healing) {
+        let Ok(transform) = entities.get(entity) else {
+            continue;
+        };
+
+        let label = damage_text.label();
+        let color = damage_text.color;
         // let max = health.max();
         let bartrans = get_sceen_transform_and_visibility(&camera_q, transform, &orbit_camera);
 
@@ -131,9 +199,7 @@ fn spawn_damage_text_children(
             .spawn(
                 // Healthbarbundle
                 DamageTextBundle {
-                    amount: DamageText {
-                        value: health.value,
-                    },
+                    amount: damage_text,
                     damage: DamageTextAttach {
                         attached_to: entity,
                     },
@@ -147,11 +213,11 @@ fn spawn_damage_text_children(
                         },
                         text: Text {
                             sections: vec![TextSection {
-                                value: format!("{current}"),
+                                value: label,
                                 style: TextStyle {
                                     font: asset_server.load("Rosela.ttf"),
                                     font_size: 100.0,
-                                    color: Color::WHITE,
+                                    color,
                                 },
                             }],
                             ..Default::default()
@@ -166,8 +232,6 @@ fn spawn_damage_text_children(
             .insert(DamageTextLifetime {
                 timer: Timer::from_seconds(2.0, TimerMode::Once),
             });
-
-        commands.entity(entity).remove::<AppliedDamage>();
     }
 }
 
@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_xpbd_3d::prelude::LinearVelocity;
+
+use crate::{
+    auras::Auras,
+    character_controller::Player,
+    crowd_control::Fleeing,
+    damage::{damage_resolution_system, DamageDealt},
+    health::Health,
+    lifetime::Lifetime,
+    spells::Casting,
+};
+
+/// How long the corpse of a unit that doesn't respawn lies around before it is despawned.
+const CORPSE_DECAY: Duration = Duration::from_secs(30);
+
+/// Marks a unit whose health ran out. Dead units can't move, cast or be hit, and stay around
+/// as a corpse until they are resurrected or decay.
+#[derive(Component)]
+pub struct Dead;
+
+/// Where a unit comes back when it is released from its corpse, and with how much health.
+#[derive(Component)]
+pub struct Respawn {
+    pub point: Vec3,
+    /// Fraction of max health to come back with, between 0 and 1.
+    pub health: f32,
+}
+
+/// Sent when a unit dies.
+#[derive(Event)]
+pub struct Died {
+    pub entity: Entity,
+    /// Whoever dealt the killing blow, if anyone.
+    pub killer: Option<Entity>,
+}
+
+/// Brings a dead unit back to life.
+#[derive(Event)]
+pub struct Resurrect {
+    pub target: Entity,
+    /// Health to come back with, capped to max health.
+    pub health: u32,
+    /// Where to come back, right at the corpse if `None`.
+    pub at: Option<Vec3>,
+}
+
+/// Sent when a [`Resurrect`] actually brought a dead unit back.
+#[derive(Event)]
+pub struct Resurrected {
+    pub entity: Entity,
+    pub health: u32,
+}
+
+/// Turns every unit whose health ran out into a corpse.
+pub fn death_system(
+    mut commands: Commands,
+    mut damage_dealt_events: EventReader<DamageDealt>,
+    mut died_events: EventWriter<Died>,
+    mut units: Query<
+        (
+            Entity,
+            &Health,
+            Option<&mut Auras>,
+            Option<&mut LinearVelocity>,
+            Has<Respawn>,
+        ),
+        Without<Dead>,
+    >,
+) {
+    // the last one to damage a unit dealt the killing blow
+    let killers: HashMap<Entity, Option<Entity>> = damage_dealt_events
+        .read()
+        .filter(|event| event.amount > 0)
+        .map(|event| (event.target, event.source))
+        .collect();
+
+    for (entity, health, auras, velocity, respawns) in &mut units {
+        if health.current > 0 {
+            continue;
+        }
+
+        if let Some(mut auras) = auras {
+            auras.clear_on_death();
+        }
+
+        if let Some(mut velocity) = velocity {
+            velocity.x = 0.0;
+            velocity.z = 0.0;
+        }
+
+        let mut corpse = commands.entity(entity);
+
+        corpse.insert(Dead).remove::<(Casting, Fleeing)>();
+
+        if !respawns {
+            corpse.insert(Lifetime {
+                timer: Timer::new(CORPSE_DECAY, TimerMode::Once),
+            });
+        }
+
+        died_events.send(Died {
+            entity,
+            killer: killers.get(&entity).copied().flatten(),
+        });
+    }
+}
+
+/// Releases the dead player to their respawn point.
+pub fn release_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    player: Query<(Entity, &Health, &Respawn), (With<Player>, With<Dead>)>,
+    mut resurrect_events: EventWriter<Resurrect>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Return) {
+        return;
+    }
+
+    let Ok((entity, health, respawn)) = player.get_single() else {
+        return;
+    };
+
+    resurrect_events.send(Resurrect {
+        target: entity,
+        health: (health.max as f32 * respawn.health).round() as u32,
+        at: Some(respawn.point),
+    });
+}
+
+pub fn resurrect_system(
+    mut commands: Commands,
+    mut resurrect_events: EventReader<Resurrect>,
+    mut resurrected_events: EventWriter<Resurrected>,
+    mut corpses: Query<(&mut Health, &mut Transform), With<Dead>>,
+) {
+    for event in resurrect_events.read() {
+        let Ok((mut health, mut transform)) = corpses.get_mut(event.target) else {
+            continue;
+        };
+
+        // coming back with no health at all would be dying again right away
+        health.current = event.health.clamp(1, health.max.max(1));
+
+        if let Some(at) = event.at {
+            transform.translation = at;
+        }
+
+        commands.entity(event.target).remove::<(Dead, Lifetime)>();
+
+        resurrected_events.send(Resurrected {
+            entity: event.target,
+            health: health.current,
+        });
+    }
+}
+
+pub struct DeathPlugin;
+
+impl Plugin for DeathPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Died>();
+        app.add_event::<Resurrect>();
+        app.add_event::<Resurrected>();
+        app.add_systems(
+            Update,
+            (
+                death_system.after(damage_resolution_system),
+                release_system,
+                resurrect_system,
+            ),
+        );
+    }
+}
@@ -1,7 +1,10 @@
 use bevy::prelude::*;
 use bevy_xpbd_3d::components::RigidBody;
 
-use crate::{health::Health, hit_box::HitBox};
+use crate::{
+    auras::Auras, damage::Resistances, health::Health, hit_box::HitBox, hit_table::CombatStats,
+    spells::SpellSchool,
+};
 
 #[derive(Debug, Component)]
 pub struct Enemy;
@@ -60,6 +63,19 @@ pub fn spawner(
 
             let default_capsule = Mesh::from(shape::Capsule::default());
 
+            // every enemy shrugs off one school and is weak to another
+            let resistances = match i {
+                1 => Resistances::default()
+                    .with_resistance(SpellSchool::Shadow, 100)
+                    .with_resistance(SpellSchool::Fire, -50),
+                2 => Resistances::default()
+                    .with_resistance(SpellSchool::Fire, 100)
+                    .with_resistance(SpellSchool::Frost, -50),
+                _ => Resistances::default()
+                    .with_armor(400)
+                    .with_resistance(SpellSchool::Shadow, -50),
+            };
+
             // let capsule = Collider::capsule(Vect::, b, 1.0);
 
             commands
@@ -74,6 +90,15 @@ pub fn spawner(
                     max: 150,
                 })
                 .insert(Enemy)
+                .insert(Name::new(format!("Enemy {}", i)))
+                .insert(Auras::default())
+                .insert(resistances)
+                .insert(CombatStats {
+                    dodge: 0.05,
+                    parry: 0.05,
+                    block: 0.1,
+                    ..Default::default()
+                })
                 .insert(HitBox {
                     radius: 1,
                     height: 1,
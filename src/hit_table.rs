@@ -0,0 +1,267 @@
+use bevy::prelude::*;
+
+use crate::{rng::CombatRng, spells::SpellSchool};
+
+/// Chance for any hit to miss, before the attacker's hit chance.
+const BASE_MISS_CHANCE: f32 = 0.05;
+
+/// How much more damage a critical hit deals.
+const CRIT_MULTIPLIER: f32 = 2.0;
+
+/// How much of a blocked hit's damage is blocked.
+const BLOCK_MITIGATION: f32 = 0.3;
+
+/// How a single hit landed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HitOutcome {
+    #[default]
+    Hit,
+    Crit,
+    Miss,
+    Dodge,
+    Parry,
+    /// Landed, but partially blocked.
+    Block,
+}
+
+impl HitOutcome {
+    /// Whether any damage went through.
+    pub fn lands(&self) -> bool {
+        matches!(self, HitOutcome::Hit | HitOutcome::Crit | HitOutcome::Block)
+    }
+
+    /// Applies the outcome to the damage of a hit.
+    pub fn apply(&self, amount: u32) -> u32 {
+        match self {
+            HitOutcome::Hit => amount,
+            HitOutcome::Crit => (amount as f32 * CRIT_MULTIPLIER).round() as u32,
+            HitOutcome::Block => (amount as f32 * (1.0 - BLOCK_MITIGATION)).round() as u32,
+            HitOutcome::Miss | HitOutcome::Dodge | HitOutcome::Parry => 0,
+        }
+    }
+}
+
+/// Chances, between 0 and 1, that the hit table is rolled against.
+/// The attacker's side is `hit` and `crit`, the defender's side `dodge`, `parry` and `block`.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct CombatStats {
+    /// Taken off the base chance to miss.
+    pub hit: f32,
+    pub crit: f32,
+    pub dodge: f32,
+    pub parry: f32,
+    pub block: f32,
+}
+
+impl Default for CombatStats {
+    fn default() -> Self {
+        CombatStats {
+            hit: 0.0,
+            crit: 0.05,
+            dodge: 0.0,
+            parry: 0.0,
+            block: 0.0,
+        }
+    }
+}
+
+/// What kind of damage the hit table is rolled for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HitKind {
+    /// Physical hits can be missed, dodged, parried, blocked or crit.
+    Physical,
+    /// Spells of any other school can only be missed or crit.
+    Spell,
+    /// Ticks of damage over time always land, but can crit.
+    Periodic,
+}
+
+impl HitKind {
+    pub fn of(school: SpellSchool, periodic: bool) -> Self {
+        match (school, periodic) {
+            (_, true) => HitKind::Periodic,
+            (SpellSchool::Physical, false) => HitKind::Physical,
+            (_, false) => HitKind::Spell,
+        }
+    }
+}
+
+/// Rolls a single hit on the table: one roll, with miss, dodge, parry, block and crit each
+/// taking up their chance of it in that order, and whatever is left being a regular hit.
+pub fn roll_hit(
+    rng: &mut CombatRng,
+    kind: HitKind,
+    attacker: CombatStats,
+    defender: CombatStats,
+) -> HitOutcome {
+    outcome_of(rng.range(0.0, 1.0), kind, attacker, defender)
+}
+
+/// Where `roll`, between 0 and 1, lands on the table.
+fn outcome_of(
+    roll: f32,
+    kind: HitKind,
+    attacker: CombatStats,
+    defender: CombatStats,
+) -> HitOutcome {
+    let miss = (BASE_MISS_CHANCE - attacker.hit).max(0.0);
+
+    let table = match kind {
+        HitKind::Physical => [
+            (HitOutcome::Miss, miss),
+            (HitOutcome::Dodge, defender.dodge),
+            (HitOutcome::Parry, defender.parry),
+            (HitOutcome::Block, defender.block),
+            (HitOutcome::Crit, attacker.crit),
+        ],
+        HitKind::Spell => [
+            (HitOutcome::Miss, miss),
+            (HitOutcome::Dodge, 0.0),
+            (HitOutcome::Parry, 0.0),
+            (HitOutcome::Block, 0.0),
+            (HitOutcome::Crit, attacker.crit),
+        ],
+        HitKind::Periodic => [
+            (HitOutcome::Miss, 0.0),
+            (HitOutcome::Dodge, 0.0),
+            (HitOutcome::Parry, 0.0),
+            (HitOutcome::Block, 0.0),
+            (HitOutcome::Crit, attacker.crit),
+        ],
+    };
+
+    let mut ceiling = 0.0;
+
+    for (outcome, chance) in table {
+        ceiling += chance.max(0.0);
+
+        if roll < ceiling {
+            return outcome;
+        }
+    }
+
+    HitOutcome::Hit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defender() -> CombatStats {
+        CombatStats {
+            hit: 0.0,
+            crit: 0.0,
+            dodge: 0.1,
+            parry: 0.1,
+            block: 0.1,
+        }
+    }
+
+    fn attacker() -> CombatStats {
+        CombatStats {
+            crit: 0.2,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn physical_table_is_rolled_in_order() {
+        let outcome = |roll| outcome_of(roll, HitKind::Physical, attacker(), defender());
+
+        // miss 0..0.05, dodge ..0.15, parry ..0.25, block ..0.35, crit ..0.55, hit after
+        assert_eq!(outcome(0.0), HitOutcome::Miss);
+        assert_eq!(outcome(0.049), HitOutcome::Miss);
+        assert_eq!(outcome(0.05), HitOutcome::Dodge);
+        assert_eq!(outcome(0.149), HitOutcome::Dodge);
+        assert_eq!(outcome(0.15), HitOutcome::Parry);
+        assert_eq!(outcome(0.25), HitOutcome::Block);
+        assert_eq!(outcome(0.35), HitOutcome::Crit);
+        assert_eq!(outcome(0.549), HitOutcome::Crit);
+        assert_eq!(outcome(0.55), HitOutcome::Hit);
+        assert_eq!(outcome(0.999), HitOutcome::Hit);
+    }
+
+    #[test]
+    fn hit_chance_removes_misses() {
+        let attacker = CombatStats {
+            hit: BASE_MISS_CHANCE,
+            ..attacker()
+        };
+
+        assert_eq!(
+            outcome_of(0.0, HitKind::Physical, attacker, defender()),
+            HitOutcome::Dodge
+        );
+    }
+
+    #[test]
+    fn avoidance_pushes_crits_off_a_full_table() {
+        let defender = CombatStats {
+            dodge: 0.5,
+            parry: 0.5,
+            ..defender()
+        };
+
+        for roll in [0.1, 0.5, 0.9, 0.999] {
+            let outcome = outcome_of(roll, HitKind::Physical, attacker(), defender);
+
+            assert!(!matches!(outcome, HitOutcome::Crit | HitOutcome::Hit));
+        }
+    }
+
+    #[test]
+    fn spells_and_ticks_are_never_avoided() {
+        let defender = CombatStats {
+            dodge: 1.0,
+            parry: 1.0,
+            block: 1.0,
+            ..defender()
+        };
+        let mut rng = CombatRng::seeded(7);
+
+        for _ in 0..1000 {
+            for kind in [HitKind::Spell, HitKind::Periodic] {
+                let outcome = roll_hit(&mut rng, kind, attacker(), defender);
+
+                assert!(!matches!(
+                    outcome,
+                    HitOutcome::Dodge | HitOutcome::Parry | HitOutcome::Block
+                ));
+
+                if kind == HitKind::Periodic {
+                    assert_ne!(outcome, HitOutcome::Miss);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn kind_follows_school() {
+        assert_eq!(HitKind::of(SpellSchool::Physical, false), HitKind::Physical);
+        assert_eq!(HitKind::of(SpellSchool::Fire, false), HitKind::Spell);
+        assert_eq!(HitKind::of(SpellSchool::Physical, true), HitKind::Periodic);
+    }
+
+    #[test]
+    fn seeded_rolls_repeat() {
+        let roll = |seed| {
+            let mut rng = CombatRng::seeded(seed);
+
+            (0..100)
+                .map(|_| roll_hit(&mut rng, HitKind::Physical, attacker(), defender()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(roll(42), roll(42));
+    }
+
+    #[test]
+    fn outcomes_change_damage() {
+        assert_eq!(HitOutcome::Hit.apply(10), 10);
+        assert_eq!(HitOutcome::Crit.apply(10), 20);
+        assert_eq!(HitOutcome::Block.apply(10), 7);
+        assert_eq!(HitOutcome::Miss.apply(10), 0);
+        assert_eq!(HitOutcome::Dodge.apply(10), 0);
+        assert_eq!(HitOutcome::Parry.apply(10), 0);
+    }
+}
@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+
+use crate::spells::{CastSpellInit, CastTarget, SpellId};
+
+/// Which key casts which spell, in the order they are laid out on the action bar.
+pub const KEYBINDINGS: [(KeyCode, &str); 10] = [
+    (KeyCode::R, "attack"),
+    (KeyCode::Q, "shadow_bolt"),
+    (KeyCode::E, "drain_life"),
+    (KeyCode::T, "blink"),
+    (KeyCode::F, "rain_of_fire"),
+    (KeyCode::G, "empower"),
+    (KeyCode::V, "fear"),
+    (KeyCode::B, "barrier"),
+    (KeyCode::C, "cleanse"),
+    (KeyCode::X, "consecration"),
+];
+
+/// Casts the spell bound to every key that was just pressed.
+pub fn keybinding_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut cast_spell_init_events: EventWriter<CastSpellInit>,
+) {
+    for (key, spell_id) in KEYBINDINGS {
+        if keyboard_input.just_pressed(key) {
+            cast_spell_init_events.send(CastSpellInit {
+                spell_id: SpellId::new(spell_id),
+                target: CastTarget::None,
+            });
+        }
+    }
+}
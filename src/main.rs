@@ -1,14 +1,11 @@
 #![allow(clippy::type_complexity)]
 
-use std::time::Duration;
-
 use auras::AurasPlugin;
 use bevy::app::{Startup, Update};
-use bevy::ecs::event::EventWriter;
 
 use bevy::pbr::AmbientLight;
 use bevy::prelude::{
-    App, Color, Commands, Component, Input, KeyCode, MouseButton, Query, Res, Resource, With,
+    App, Color, Commands, Component, Input, MouseButton, Query, Res, Resource, With,
 };
 use bevy::gizmos::gizmos::Gizmos;
 
@@ -19,34 +16,47 @@ use bevy::DefaultPlugins;
 use bevy_xpbd_3d::plugins::{PhysicsDebugPlugin, PhysicsPlugins};
 use character_controller::{create_character_controller, update_character_transform};
 
+use combat_log::CombatLogPlugin;
+use damage::DamagePlugin;
 use damage_text::DamageTextPlugin;
+use death::DeathPlugin;
 use enemy::EnemyPlugin;
 use fps_measure::{FpsMeasurePlugin, setup_fps_counter, fps_text_update_system};
-use health::health_system;
 use health_bars::HealthBarPlugin;
+use keybindings::keybinding_system;
 use lifetime::LifetimePlugin;
 use map::setup_map;
 use projectile::ProjectilePlugin;
-use spells::{CastSpellInit, SpellsPlugin};
+use resource_pool::ResourcePoolPlugin;
+use spells::SpellsPlugin;
 use ui::UIPlugin;
 use bevy_mod_raycast::prelude::*;
 use bevy::diagnostic::FrameTimeDiagnosticsPlugin;
 
 pub mod character_controller;
 mod aoe;
+mod combat_log;
 mod auras;
 mod controller;
+mod crowd_control;
 mod damage;
 mod damage_text;
+mod death;
 pub mod enemy;
 mod health;
 pub mod health_bars;
 pub mod hit_box;
+mod hit_table;
+mod keybindings;
 mod lifetime;
 mod map;
 pub mod orbit_camera;
 mod particles;
+mod periodic;
+mod pulse;
 pub mod projectile;
+mod resource_pool;
+mod rng;
 mod spells;
 mod ui;
 pub mod utils;
@@ -88,9 +98,8 @@ fn main() {
             HealthBarPlugin,
             ProjectilePlugin,
             DamageTextPlugin,
-            AurasPlugin,
+            (DamagePlugin, DeathPlugin, AurasPlugin, ResourcePoolPlugin, SpellsPlugin, CombatLogPlugin),
             UIPlugin,
-            SpellsPlugin,
             aoe::AoeTargetingPlugin,
             DefaultRaycastingPlugin
         ))
@@ -103,9 +112,7 @@ fn main() {
             Update,
             (
                 cursor_grab,
-                on_mouse_shoot,
-                health_system,
-                basic_attack,
+                keybinding_system,
                 update_character_transform, // character_direction_system
                 fps_text_update_system
                 // raycast
@@ -149,34 +156,6 @@ fn cursor_grab(
     }
 }
 
-fn on_mouse_shoot(
-    buttons: Res<Input<KeyCode>>,
-    mut spell_writer: EventWriter<spells::CastSpellInit>,
-) {
-    if buttons.just_pressed(KeyCode::Q) {
-        spell_writer.send(CastSpellInit {
-            spell_id: "s".to_string(),
-            cast_time: spells::CastTime::Duration(Duration::from_secs(2)),
-            damage: 10,
-            apply_auras: vec![],
-        });
-    }
-}
-
-fn basic_attack(
-    buttons: Res<Input<KeyCode>>,
-    mut spell_writer: EventWriter<spells::CastSpellInit>,
-) {
-    if buttons.just_pressed(KeyCode::R) {
-        spell_writer.send(CastSpellInit {
-            spell_id: "a".to_string(),
-            cast_time: spells::CastTime::Instant,
-            damage: 10,
-            apply_auras: vec![],
-        });
-    }
-}
-
 // fn display_events(
 //     mut collision_events: EventReader<CollisionEvent>,
 //     // _contact_force_events: EventReader<ContactForceEvent>,
@@ -0,0 +1,191 @@
+use std::time::Duration;
+
+/// Schedules the ticks of a periodic effect, independent of the frame rate.
+/// Every tick that comes due within a frame is returned, however long the frame was,
+/// and the effect's final tick is scaled down if it didn't get a full interval in.
+pub struct PeriodicTicks {
+    interval: Duration,
+    haste: f32,
+    since_last_tick: Duration,
+}
+
+impl PeriodicTicks {
+    pub fn new(interval: Duration) -> Self {
+        PeriodicTicks {
+            interval,
+            haste: 1.0,
+            since_last_tick: Duration::ZERO,
+        }
+    }
+
+    /// Ticks `haste` times as often from now on, e.g. 1.25 for 25% haste.
+    pub fn set_haste(&mut self, haste: f32) {
+        if haste > 0.0 {
+            self.haste = haste;
+        }
+    }
+
+    /// Advances by `delta`, with `remaining` left of the effect before this frame.
+    /// Returns the size of every tick that came due as a fraction of a full tick:
+    /// 1.0 for every full interval, and less for a final tick cut short by the effect ending.
+    pub fn advance(&mut self, delta: Duration, remaining: Duration) -> Vec<f32> {
+        // time past the end of the effect doesn't count towards another tick
+        let ends = delta >= remaining;
+        let delta = delta.min(remaining);
+
+        let interval = self.interval.div_f32(self.haste);
+
+        self.since_last_tick += delta;
+
+        let mut ticks = Vec::new();
+
+        if interval.is_zero() {
+            return ticks;
+        }
+
+        while self.since_last_tick >= interval {
+            self.since_last_tick -= interval;
+            ticks.push(1.0);
+        }
+
+        if ends && !self.since_last_tick.is_zero() {
+            ticks.push(self.since_last_tick.as_secs_f32() / interval.as_secs_f32());
+            self.since_last_tick = Duration::ZERO;
+        }
+
+        ticks
+    }
+}
+
+/// Splits `exact`, a tick's amount plus whatever the previous ticks carried over,
+/// into the whole points the tick deals and the fraction of a point to carry over to the next.
+/// Whatever is still carried over after the effect's final tick is dropped,
+/// so over its whole duration an effect deals its exact total rounded down.
+pub fn whole_amount(exact: f32) -> (u32, f32) {
+    // a little leeway, so that e.g. two halves make a whole point
+    let whole = (exact + 1e-4).floor();
+
+    // the leeway can leave a remainder just below zero, which isn't carried over as a debt
+    (whole as u32, (exact - whole).max(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(ticks: &[f32]) -> f32 {
+        ticks.iter().sum()
+    }
+
+    #[test]
+    fn long_frame_returns_every_tick_due() {
+        let mut ticks = PeriodicTicks::new(Duration::from_secs(1));
+
+        let due = ticks.advance(Duration::from_millis(3500), Duration::from_secs(10));
+
+        assert_eq!(due, vec![1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn carries_time_over_between_frames() {
+        let mut ticks = PeriodicTicks::new(Duration::from_secs(1));
+
+        assert!(ticks
+            .advance(Duration::from_millis(600), Duration::from_secs(10))
+            .is_empty());
+        assert_eq!(
+            ticks.advance(Duration::from_millis(600), Duration::from_millis(9400)),
+            vec![1.0]
+        );
+    }
+
+    #[test]
+    fn haste_shortens_the_interval() {
+        let mut ticks = PeriodicTicks::new(Duration::from_secs(1));
+        ticks.set_haste(2.0);
+
+        let due = ticks.advance(Duration::from_secs(2), Duration::from_secs(10));
+
+        assert_eq!(due.len(), 4);
+    }
+
+    #[test]
+    fn non_positive_haste_is_ignored() {
+        let mut ticks = PeriodicTicks::new(Duration::from_secs(1));
+        ticks.set_haste(0.0);
+
+        let due = ticks.advance(Duration::from_secs(2), Duration::from_secs(10));
+
+        assert_eq!(due.len(), 2);
+    }
+
+    #[test]
+    fn final_tick_is_partial() {
+        let mut ticks = PeriodicTicks::new(Duration::from_secs(1));
+        let duration = Duration::from_millis(5500);
+        let frame = Duration::from_millis(100);
+
+        let mut remaining = duration;
+        let mut due = Vec::new();
+
+        while !remaining.is_zero() {
+            due.extend(ticks.advance(frame, remaining));
+            remaining = remaining.saturating_sub(frame);
+        }
+
+        assert_eq!(due.len(), 6);
+        assert!((total(&due) - 5.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn time_past_the_end_does_not_count() {
+        let mut ticks = PeriodicTicks::new(Duration::from_secs(1));
+
+        let due = ticks.advance(Duration::from_secs(10), Duration::from_millis(2500));
+
+        assert_eq!(due.len(), 3);
+        assert!((total(&due) - 2.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn halves_make_a_whole_point() {
+        let (first, carried) = whole_amount(0.5);
+        let (second, carried) = whole_amount(0.5 + carried);
+
+        assert_eq!((first, second), (0, 1));
+        assert_eq!(carried, 0.0);
+    }
+
+    #[test]
+    fn drift_below_a_whole_point_still_counts() {
+        assert_eq!(whole_amount(2.99995), (3, 0.0));
+    }
+
+    #[test]
+    fn hasted_dot_deals_its_total_rounded_down() {
+        // 3 per tick over 5.5s with 30% haste: 7.15 ticks, 21.45 in total
+        let mut ticks = PeriodicTicks::new(Duration::from_secs(1));
+        ticks.set_haste(1.3);
+
+        let frame = Duration::from_millis(16);
+        let mut remaining = Duration::from_millis(5500);
+        let mut carried = 0.0;
+        let mut dealt = Vec::new();
+
+        while !remaining.is_zero() {
+            for fraction in ticks.advance(frame, remaining) {
+                let (amount, remainder) = whole_amount(3.0 * fraction + carried);
+
+                carried = remainder;
+                dealt.push(amount);
+            }
+
+            remaining = remaining.saturating_sub(frame);
+        }
+
+        assert_eq!(dealt, vec![3, 3, 3, 3, 3, 3, 3, 0]);
+        assert_eq!(dealt.iter().sum::<u32>(), 21);
+        // dropped once the effect is over
+        assert!((carried - 0.45).abs() < 1e-3);
+    }
+}
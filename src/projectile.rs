@@ -1,12 +1,18 @@
 use crate::{
-    damage::Damage, damage_text::spawn_damage_text_on_entity, health::Health, hit_box::HitBox,
+    damage::{Damage, DamageRequest},
+    death::Dead,
+    health::Health,
+    hit_box::HitBox,
+    spells::SpellId,
     utils::safe_minus,
 };
 use bevy::prelude::*;
-use std::cmp::min;
 
 #[derive(Component)]
 pub struct Projectile {
+    /// Whoever fired the projectile.
+    pub source: Option<Entity>,
+    pub spell_id: SpellId,
     pub despawn_after_hit: bool,
     pub direction: Vec3,
     pub speed: f32,
@@ -14,10 +20,11 @@ pub struct Projectile {
 
 pub fn projectile_system(
     mut commands: Commands,
-    mut hitboxes: Query<(Entity, &Transform, &HitBox, &mut Health)>,
+    mut damage_requests: EventWriter<DamageRequest>,
+    hitboxes: Query<(Entity, &Transform, &HitBox), (With<Health>, Without<Dead>)>,
     projectiles: Query<(Entity, &Transform, &Projectile, &Damage)>,
 ) {
-    for (entity, transform, hitbox, mut health) in hitboxes.iter_mut() {
+    for (entity, transform, hitbox) in hitboxes.iter() {
         for (projectile_entity, projectile_transform, projectile, damage) in projectiles.iter() {
             // calculate that a projectile is in the range
             // let  = transform.translation.z - projectile_transform.translation.z;
@@ -31,9 +38,13 @@ pub fn projectile_system(
             .sqrt();
 
             if distance < hitbox.radius as f32 {
-                spawn_damage_text_on_entity(&mut commands, entity, damage.amount);
-
-                health.current = health.current - min(damage.amount, health.current);
+                damage_requests.send(DamageRequest::new(
+                    projectile.source,
+                    entity,
+                    &projectile.spell_id,
+                    damage.school,
+                    damage.amount,
+                ));
 
                 if projectile.despawn_after_hit {
                     commands.entity(projectile_entity).despawn();
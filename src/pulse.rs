@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    auras::{AuraEffect, Auras},
+    enemy::Enemy,
+    spells::{CastTarget, SpellEffect, SpellEffectExecutor, SpellId, SpellSchool},
+};
+
+/// Who a pulsing aura affects, relative to whoever holds it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum PulseTargets {
+    /// The holder and everyone on its side.
+    Allies,
+    Enemies,
+    Everyone,
+}
+
+impl PulseTargets {
+    pub fn includes(&self, holder_is_enemy: bool, unit_is_enemy: bool) -> bool {
+        match self {
+            PulseTargets::Allies => holder_is_enemy == unit_is_enemy,
+            PulseTargets::Enemies => holder_is_enemy != unit_is_enemy,
+            PulseTargets::Everyone => true,
+        }
+    }
+}
+
+/// Sent by the aura system every time a pulsing aura comes due.
+#[derive(Event)]
+pub struct AuraPulsed {
+    pub holder: Entity,
+    /// Whoever applied the aura, if anyone. The pulse's effects are theirs.
+    pub source: Option<Entity>,
+    pub spell_id: SpellId,
+    pub school: SpellSchool,
+    pub radius: f32,
+    pub targets: PulseTargets,
+    pub effects: Vec<SpellEffect>,
+}
+
+/// Resolves the effects of every pulse against the units around its holder.
+pub fn aura_pulse_system(
+    mut aura_pulsed_events: EventReader<AuraPulsed>,
+    factions: Query<Has<Enemy>>,
+    mut executor: SpellEffectExecutor,
+) {
+    for event in aura_pulsed_events.read() {
+        let Ok(holder_is_enemy) = factions.get(event.holder) else {
+            continue;
+        };
+
+        let targets: Vec<Entity> = executor
+            .units_around(event.holder, event.radius)
+            .into_iter()
+            .filter(|unit| {
+                factions.get(*unit).is_ok_and(|unit_is_enemy| {
+                    event.targets.includes(holder_is_enemy, unit_is_enemy)
+                })
+            })
+            .collect();
+
+        executor.apply(
+            event.source.unwrap_or(event.holder),
+            &event.spell_id,
+            &event.spell_id.0,
+            event.school,
+            &event.effects,
+            CastTarget::Unit(event.holder),
+            &targets,
+        );
+    }
+}
+
+/// Draws the area of every pulsing aura around its holder.
+pub fn pulse_gizmo_system(mut gizmos: Gizmos, holders: Query<(&Transform, &Auras)>) {
+    for (transform, auras) in &holders {
+        for aura in auras.iter() {
+            if let AuraEffect::Pulse {
+                radius, targets, ..
+            } = &aura.effect
+            {
+                let color = match targets {
+                    PulseTargets::Allies => Color::GREEN,
+                    PulseTargets::Enemies | PulseTargets::Everyone => Color::ORANGE_RED,
+                };
+
+                gizmos.circle(transform.translation, Vec3::Y, *radius, color);
+            }
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::damage::DamageDealt;
+
+/// Rage gained per point of damage dealt and taken. Taking a beating builds more of it.
+const RAGE_PER_DAMAGE_DEALT: f32 = 0.5;
+const RAGE_PER_DAMAGE_TAKEN: f32 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum ResourceType {
+    Mana,
+    Energy,
+    Rage,
+}
+
+impl ResourceType {
+    /// Points regenerated per second. Negative values decay the pool instead.
+    pub fn regeneration_per_second(&self, max: u32) -> f32 {
+        match self {
+            // 2% of the pool every second
+            ResourceType::Mana => max as f32 * 0.02,
+            ResourceType::Energy => 10.0,
+            ResourceType::Rage => -1.0,
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            ResourceType::Mana => Color::BLUE,
+            ResourceType::Energy => Color::YELLOW,
+            ResourceType::Rage => Color::RED,
+        }
+    }
+}
+
+/// The amount of a resource that a spell consumes when it fires.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct ResourceCost {
+    pub resource_type: ResourceType,
+    pub amount: u32,
+}
+
+/// A secondary resource (next to [`crate::health::Health`]) that spells are paid with.
+#[derive(Component)]
+pub struct ResourcePool {
+    pub resource_type: ResourceType,
+    pub max: u32,
+    pub current: u32,
+    // regeneration that has not yet added up to a whole point
+    partial: f32,
+}
+
+impl ResourcePool {
+    pub fn new(resource_type: ResourceType, max: u32) -> Self {
+        // rage is built up in combat, everything else starts full
+        let current = match resource_type {
+            ResourceType::Rage => 0,
+            _ => max,
+        };
+
+        ResourcePool {
+            resource_type,
+            max,
+            current,
+            partial: 0.0,
+        }
+    }
+
+    pub fn can_afford(&self, cost: &ResourceCost) -> bool {
+        self.resource_type == cost.resource_type && self.current >= cost.amount
+    }
+
+    /// Deducts the cost, returning `false` (and leaving the pool untouched) if it can't be paid.
+    pub fn spend(&mut self, cost: &ResourceCost) -> bool {
+        if !self.can_afford(cost) {
+            return false;
+        }
+
+        self.current -= cost.amount;
+
+        true
+    }
+
+    pub fn gain(&mut self, amount: u32) {
+        self.current = (self.current + amount).min(self.max);
+    }
+
+    /// Builds up rage from `damage` dealt or taken, at least a point for any damage at all.
+    /// Pools of any other resource are left alone.
+    pub fn gain_rage(&mut self, damage: u32, per_damage: f32) {
+        if self.resource_type != ResourceType::Rage || damage == 0 {
+            return;
+        }
+
+        self.gain(((damage as f32 * per_damage).round() as u32).max(1));
+    }
+
+    pub fn regenerate(&mut self, delta: Duration) {
+        self.partial += self.resource_type.regeneration_per_second(self.max) * delta.as_secs_f32();
+
+        let whole = self.partial.trunc();
+        self.partial -= whole;
+
+        if whole > 0.0 {
+            self.gain(whole as u32);
+        } else if whole < 0.0 {
+            self.current = self.current.saturating_sub(-whole as u32);
+        }
+
+        // don't bank regeneration past the bounds of the pool
+        if (self.current == self.max && self.partial > 0.0)
+            || (self.current == 0 && self.partial < 0.0)
+        {
+            self.partial = 0.0;
+        }
+    }
+}
+
+fn resource_regeneration_system(mut pools: Query<&mut ResourcePool>, time: Res<Time>) {
+    for mut pool in &mut pools {
+        pool.regenerate(time.delta());
+    }
+}
+
+fn rage_generation_system(
+    mut damage_dealt_events: EventReader<DamageDealt>,
+    mut pools: Query<&mut ResourcePool>,
+) {
+    for event in damage_dealt_events.read() {
+        if let Some(mut pool) = event.source.and_then(|source| pools.get_mut(source).ok()) {
+            pool.gain_rage(event.amount, RAGE_PER_DAMAGE_DEALT);
+        }
+
+        if let Ok(mut pool) = pools.get_mut(event.target) {
+            pool.gain_rage(event.amount, RAGE_PER_DAMAGE_TAKEN);
+        }
+    }
+}
+
+pub struct ResourcePoolPlugin;
+
+impl Plugin for ResourcePoolPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (resource_regeneration_system, rage_generation_system),
+        );
+    }
+}
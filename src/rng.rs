@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+
+/// Random number generator for every roll made in combat.
+/// Set `COMBAT_SEED` to get the same rolls on every run.
+#[derive(Resource)]
+pub struct CombatRng(fastrand::Rng);
+
+impl CombatRng {
+    pub fn seeded(seed: u64) -> Self {
+        CombatRng(fastrand::Rng::with_seed(seed))
+    }
+
+    /// A number between `min` and `max`.
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + self.0.f32() * (max - min)
+    }
+
+    /// Returns `true` with the given chance, between 0 and 1.
+    pub fn roll(&mut self, chance: f32) -> bool {
+        self.0.f32() < chance
+    }
+}
+
+impl Default for CombatRng {
+    fn default() -> Self {
+        match std::env::var("COMBAT_SEED")
+            .ok()
+            .and_then(|seed| seed.parse().ok())
+        {
+            Some(seed) => CombatRng::seeded(seed),
+            None => CombatRng(fastrand::Rng::new()),
+        }
+    }
+}
@@ -1,34 +1,146 @@
 use bevy::prelude::*;
 use std::time::Duration;
 
-use crate::character_controller::Player;
+use crate::{character_controller::Player, health::Health, resource_pool::ResourcePool};
 
-use super::CastSpellFire;
+use super::{
+    can_afford, CastFailed, CastFailedReason, CastSpellFire, CastTarget, SpellBook,
+    SpellDefinition, SpellId, SpellSchool,
+};
 
 #[derive(Component)]
 pub struct Casting {
     pub current_duration: Duration,
     pub total_duration: Duration,
-    pub spell_id: String,
+    pub spell_id: SpellId,
+    pub target: CastTarget,
+    pub school: SpellSchool,
+    pub cast_while_moving: bool,
+    /// How many times this cast has been pushed back by damage.
+    pub pushbacks: u32,
+    /// Timer for the periodic ticks of a channeled spell, `None` for regular casts.
+    pub channel: Option<Timer>,
+}
+
+impl Casting {
+    pub fn new(
+        spell_id: SpellId,
+        target: CastTarget,
+        spell: &SpellDefinition,
+        total_duration: Duration,
+    ) -> Self {
+        Casting {
+            current_duration: Duration::ZERO,
+            total_duration,
+            spell_id,
+            target,
+            school: spell.school,
+            cast_while_moving: spell.cast_while_moving,
+            pushbacks: 0,
+            channel: None,
+        }
+    }
+
+    pub fn channel(
+        spell_id: SpellId,
+        target: CastTarget,
+        spell: &SpellDefinition,
+        total_duration: Duration,
+        tick_interval: Duration,
+    ) -> Self {
+        Casting {
+            channel: Some(Timer::new(tick_interval, TimerMode::Repeating)),
+            ..Casting::new(spell_id, target, spell, total_duration)
+        }
+    }
+
+    pub fn is_channel(&self) -> bool {
+        self.channel.is_some()
+    }
 }
 
 // pub fn setup_cast_bar() {}
 
+#[allow(clippy::too_many_arguments)]
 pub fn casting_system(
     // this should also be the player
-    mut caster_query: Query<(Entity, &mut Casting), With<Player>>,
+    mut caster_query: Query<
+        (Entity, &Transform, &mut Casting, Option<&mut ResourcePool>),
+        With<Player>,
+    >,
+    units: Query<&Transform, With<Health>>,
     time: Res<Time>,
+    spell_book: Res<SpellBook>,
+    definitions: Res<Assets<SpellDefinition>>,
     mut cast_spell_fire_events: EventWriter<CastSpellFire>,
+    mut cast_failed_events: EventWriter<CastFailed>,
     mut commands: Commands,
 ) {
-    for (entity, mut casting) in &mut caster_query {
+    for (entity, transform, mut casting, mut pool) in &mut caster_query {
+        let remaining = casting
+            .total_duration
+            .saturating_sub(casting.current_duration);
         casting.current_duration = time.delta() + casting.current_duration;
 
+        // channels were paid for up front and fire on every tick instead of at the end
+        let ticks = casting.channel.as_mut().map(|timer| {
+            // ticks past the end of the channel don't count
+            timer.tick(time.delta().min(remaining));
+            timer.times_finished_this_tick()
+        });
+
+        if let Some(ticks) = ticks {
+            for _ in 0..ticks {
+                cast_spell_fire_events.send(CastSpellFire {
+                    caster: entity,
+                    id: casting.spell_id.clone(),
+                    target: casting.target,
+                    channel_tick: true,
+                });
+            }
+
+            if casting.current_duration >= casting.total_duration {
+                commands.entity(entity).remove::<Casting>();
+            }
+
+            continue;
+        }
+
         if casting.current_duration > casting.total_duration {
+            commands.entity(entity).remove::<Casting>();
+
+            let Some(spell) = spell_book.get(&casting.spell_id, &definitions) else {
+                continue;
+            };
+
+            // the resource may have been spent and the target may have moved while we were casting
+            let failure = if !can_afford(spell, pool.as_deref()) {
+                Err(CastFailedReason::NotEnoughResource)
+            } else {
+                spell
+                    .targeting
+                    .validate(spell.range, transform, casting.target.position(&units))
+            };
+
+            if let Err(reason) = failure {
+                cast_failed_events.send(CastFailed {
+                    caster: entity,
+                    spell_id: casting.spell_id.clone(),
+                    reason,
+                });
+                continue;
+            }
+
+            if let (Some(cost), Some(pool)) = (&spell.cost, pool.as_deref_mut()) {
+                pool.spend(cost);
+            }
+
             cast_spell_fire_events.send(CastSpellFire {
+                caster: entity,
                 id: casting.spell_id.clone(),
+                target: casting.target,
+                channel_tick: false,
             });
-            commands.entity(entity).remove::<Casting>();
         }
     }
 }
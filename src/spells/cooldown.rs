@@ -0,0 +1,232 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::HashMap};
+
+use super::{CastSpellFire, Charges, SpellBook, SpellDefinition, SpellId, SpellSchool};
+
+/// How long every spell that triggers the global cooldown locks out the others.
+pub const GLOBAL_COOLDOWN: Duration = Duration::from_millis(1500);
+
+/// Cooldown timers of a caster, both per spell and global,
+/// as well as any spell schools that have been locked out by an interrupt.
+#[derive(Component, Default)]
+pub struct Cooldowns {
+    global: Option<Timer>,
+    spells: HashMap<SpellId, Timer>,
+    schools: HashMap<SpellSchool, Timer>,
+    // spells with charges are only tracked while they are missing a charge
+    charges: HashMap<SpellId, ChargeState>,
+}
+
+/// Charges left on a spell, with the timer for the next one to come back.
+pub struct ChargeState {
+    pub available: u32,
+    pub max: u32,
+    pub recharge: Timer,
+}
+
+impl ChargeState {
+    fn full(charges: &Charges) -> Self {
+        ChargeState {
+            available: charges.max,
+            max: charges.max,
+            recharge: Timer::new(charges.recharge, TimerMode::Repeating),
+        }
+    }
+
+    fn consume(&mut self) {
+        // only restart the recharge if nothing was recharging already
+        if self.available == self.max {
+            self.recharge.reset();
+        }
+
+        self.available = self.available.saturating_sub(1);
+    }
+
+    /// Returns `true` once every charge is back.
+    fn tick(&mut self, delta: Duration) -> bool {
+        // a long frame can bring back several charges, and whatever it ran over by
+        // already counts towards the next one
+        let recharged = self.recharge.tick(delta).times_finished_this_tick();
+
+        self.available = (self.available + recharged).min(self.max);
+
+        self.available >= self.max
+    }
+}
+
+impl Cooldowns {
+    pub fn start_global(&mut self, duration: Duration) {
+        self.global = Some(Timer::new(duration, TimerMode::Once));
+    }
+
+    /// Puts the spell on cooldown, or uses up one of its charges if it has any.
+    pub fn start(&mut self, spell_id: SpellId, spell: &SpellDefinition) {
+        if let Some(charges) = &spell.charges {
+            self.charges
+                .entry(spell_id)
+                .or_insert_with(|| ChargeState::full(charges))
+                .consume();
+        } else if spell.cooldown > Duration::ZERO {
+            self.spells
+                .insert(spell_id, Timer::new(spell.cooldown, TimerMode::Once));
+        }
+    }
+
+    /// The cooldown that is currently stopping this spell from being cast,
+    /// i.e. the longest of its own cooldown (or recharge, once it is out of charges)
+    /// and the global cooldown.
+    pub fn blocking(&self, spell_id: &SpellId) -> Option<&Timer> {
+        let recharge = self
+            .charges
+            .get(spell_id)
+            .filter(|charges| charges.available == 0)
+            .map(|charges| &charges.recharge);
+
+        [self.global.as_ref(), self.spells.get(spell_id), recharge]
+            .into_iter()
+            .flatten()
+            .max_by_key(|timer| timer.remaining())
+    }
+
+    /// Charges left on the spell, or `None` if it doesn't use charges.
+    pub fn charges(&self, spell_id: &SpellId, spell: &SpellDefinition) -> Option<u32> {
+        spell.charges.map(|charges| {
+            self.charges
+                .get(spell_id)
+                .map_or(charges.max, |state| state.available)
+        })
+    }
+
+    pub fn lock_school(&mut self, school: SpellSchool, duration: Duration) {
+        self.schools
+            .insert(school, Timer::new(duration, TimerMode::Once));
+    }
+
+    pub fn is_school_locked(&self, school: SpellSchool) -> bool {
+        self.schools.contains_key(&school)
+    }
+
+    pub fn is_ready(&self, spell_id: &SpellId) -> bool {
+        self.blocking(spell_id).is_none()
+    }
+
+    pub fn tick(&mut self, delta: Duration) {
+        if let Some(global) = &mut self.global {
+            if global.tick(delta).finished() {
+                self.global = None;
+            }
+        }
+
+        self.spells.retain(|_, timer| !timer.tick(delta).finished());
+        self.charges.retain(|_, charges| !charges.tick(delta));
+        self.schools
+            .retain(|_, timer| !timer.tick(delta).finished());
+    }
+}
+
+pub fn cooldown_system(mut casters: Query<&mut Cooldowns>, time: Res<Time>) {
+    for mut cooldowns in &mut casters {
+        cooldowns.tick(time.delta());
+    }
+}
+
+/// Starts the spell's own cooldown once it actually fires.
+/// Channels are put on cooldown when they start instead.
+pub fn start_cooldown_system(
+    mut cast_spell_fire_events: EventReader<CastSpellFire>,
+    mut casters: Query<&mut Cooldowns>,
+    spell_book: Res<SpellBook>,
+    definitions: Res<Assets<SpellDefinition>>,
+) {
+    for event in cast_spell_fire_events.read() {
+        if event.channel_tick {
+            continue;
+        }
+
+        let (Ok(mut cooldowns), Some(spell)) = (
+            casters.get_mut(event.caster),
+            spell_book.get(&event.id, &definitions),
+        ) else {
+            continue;
+        };
+
+        cooldowns.start(event.id.clone(), spell);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn charges() -> ChargeState {
+        ChargeState::full(&Charges {
+            max: 3,
+            recharge: Duration::from_secs(10),
+        })
+    }
+
+    #[test]
+    fn consume_uses_up_charges() {
+        let mut charges = charges();
+
+        for available in [2, 1, 0, 0] {
+            charges.consume();
+            assert_eq!(charges.available, available);
+        }
+    }
+
+    #[test]
+    fn charges_come_back_one_at_a_time() {
+        let mut charges = charges();
+        charges.consume();
+        charges.consume();
+
+        assert!(!charges.tick(Duration::from_secs(9)));
+        assert_eq!(charges.available, 1);
+
+        assert!(!charges.tick(Duration::from_secs(1)));
+        assert_eq!(charges.available, 2);
+
+        assert!(charges.tick(Duration::from_secs(10)));
+        assert_eq!(charges.available, 3);
+    }
+
+    #[test]
+    fn overshoot_counts_towards_the_next_charge() {
+        let mut charges = charges();
+        charges.consume();
+        charges.consume();
+
+        charges.tick(Duration::from_secs(12));
+        assert_eq!(charges.available, 2);
+        assert_eq!(charges.recharge.remaining(), Duration::from_secs(8));
+
+        assert!(charges.tick(Duration::from_secs(8)));
+    }
+
+    #[test]
+    fn long_frame_brings_back_several_charges() {
+        let mut charges = charges();
+        charges.consume();
+        charges.consume();
+        charges.consume();
+
+        assert!(!charges.tick(Duration::from_secs(25)));
+        assert_eq!(charges.available, 2);
+
+        assert!(charges.tick(Duration::from_secs(60)));
+        assert_eq!(charges.available, 3);
+    }
+
+    #[test]
+    fn using_a_charge_while_recharging_keeps_the_recharge_going() {
+        let mut charges = charges();
+        charges.consume();
+        charges.tick(Duration::from_secs(6));
+        charges.consume();
+
+        charges.tick(Duration::from_secs(4));
+        assert_eq!(charges.available, 2);
+    }
+}
@@ -0,0 +1,162 @@
+use std::time::Duration;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext, LoadedFolder},
+    prelude::*,
+    utils::{BoxedFuture, HashMap},
+};
+use serde::{Deserialize, Deserializer};
+use thiserror::Error;
+
+use crate::resource_pool::ResourceCost;
+
+use super::{CastTime, Charges, SpellEffect, SpellSchool, TargetingMode};
+
+/// Folder (relative to `assets/`) that the [`SpellBook`] loads definitions from.
+pub const SPELL_FOLDER: &str = "spells";
+
+/// Identifier used to look a spell up in the [`SpellBook`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(transparent)]
+pub struct SpellId(pub String);
+
+impl SpellId {
+    pub fn new(id: &str) -> Self {
+        SpellId(id.to_string())
+    }
+}
+
+impl From<&str> for SpellId {
+    fn from(value: &str) -> Self {
+        SpellId::new(value)
+    }
+}
+
+/// A spell as described by a `*.spell.ron` asset file.
+#[derive(Asset, TypePath, Debug, Deserialize)]
+pub struct SpellDefinition {
+    pub id: SpellId,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub school: SpellSchool,
+    pub cast_time: CastTime,
+    /// Whether the caster may keep moving without the cast being cancelled.
+    #[serde(default)]
+    pub cast_while_moving: bool,
+    #[serde(default, deserialize_with = "duration_from_secs")]
+    pub cooldown: Duration,
+    /// Replaces the cooldown with charges that recharge one at a time.
+    #[serde(default)]
+    pub charges: Option<Charges>,
+    #[serde(default = "default_true")]
+    pub triggers_global_cooldown: bool,
+    #[serde(default)]
+    pub cost: Option<ResourceCost>,
+    pub targeting: TargetingMode,
+    /// Maximum distance (along the xz plane) at which the spell can hit.
+    pub range: f32,
+    /// What the spell does when it fires, in order.
+    pub effects: Vec<SpellEffect>,
+}
+
+pub(super) fn default_true() -> bool {
+    true
+}
+
+/// Lets designers write durations as plain seconds, e.g. `1.5`.
+pub fn duration_from_secs<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let secs = f32::deserialize(deserializer)?;
+
+    Ok(Duration::from_secs_f32(secs.max(0.0)))
+}
+
+#[derive(Debug, Error)]
+pub enum SpellDefinitionLoaderError {
+    #[error("could not read spell definition: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse spell definition: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+#[derive(Default)]
+pub struct SpellDefinitionLoader;
+
+impl AssetLoader for SpellDefinitionLoader {
+    type Asset = SpellDefinition;
+    type Settings = ();
+    type Error = SpellDefinitionLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+
+            Ok(ron::de::from_bytes::<SpellDefinition>(&bytes)?)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["spell.ron"]
+    }
+}
+
+/// Registry of every loaded [`SpellDefinition`], keyed by [`SpellId`].
+#[derive(Resource)]
+pub struct SpellBook {
+    // keeps the folder (and therefore every definition in it) loaded
+    _folder: Handle<LoadedFolder>,
+    spells: HashMap<SpellId, AssetId<SpellDefinition>>,
+}
+
+impl FromWorld for SpellBook {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+
+        SpellBook {
+            _folder: asset_server.load_folder(SPELL_FOLDER),
+            spells: HashMap::default(),
+        }
+    }
+}
+
+impl SpellBook {
+    pub fn get<'a>(
+        &self,
+        id: &SpellId,
+        definitions: &'a Assets<SpellDefinition>,
+    ) -> Option<&'a SpellDefinition> {
+        self.spells
+            .get(id)
+            .and_then(|asset_id| definitions.get(*asset_id))
+    }
+}
+
+/// Keeps the [`SpellBook`] in sync with the definitions that the asset server has loaded.
+pub fn spell_book_system(
+    mut events: EventReader<AssetEvent<SpellDefinition>>,
+    definitions: Res<Assets<SpellDefinition>>,
+    mut spell_book: ResMut<SpellBook>,
+) {
+    for event in events.read() {
+        match event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => {
+                if let Some(definition) = definitions.get(*id) {
+                    spell_book.spells.retain(|_, value| value != id);
+                    spell_book.spells.insert(definition.id.clone(), *id);
+                }
+            }
+            AssetEvent::Removed { id } => spell_book.spells.retain(|_, value| value != id),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,392 @@
+use std::time::Duration;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::Deserialize;
+
+use crate::{
+    aoe::GroundEffect,
+    auras::{apply_aura, Aura, AuraApplied, AuraInstance, Auras, DispelType},
+    damage::{Damage, DamageRequest, HealRequest},
+    death::Dead,
+    health::Health,
+    lifetime::Lifetime,
+    projectile::Projectile,
+    utils::xz_distance,
+};
+
+use super::{
+    definition::default_true, duration_from_secs, CastTarget, InterruptCast, SpellDefinition,
+    SpellId, SpellSchool, TargetingMode,
+};
+
+/// A single step of what a spell does when it fires.
+/// A spell's effects are resolved in the order they are listed.
+#[derive(Debug, Clone, Deserialize)]
+pub enum SpellEffect {
+    DirectDamage {
+        amount: u32,
+    },
+    Heal {
+        amount: u32,
+    },
+    SpawnProjectile(ProjectileDefinition),
+    ApplyAura(Aura),
+    /// Pushes every target `distance` away from the caster.
+    Knockback {
+        distance: f32,
+    },
+    /// Moves the caster `distance` forward.
+    Teleport {
+        distance: f32,
+    },
+    SpawnGroundEffect(GroundEffectDefinition),
+    /// Interrupts targets that are casting and locks out their school for `lockout` seconds.
+    Interrupt {
+        #[serde(deserialize_with = "duration_from_secs")]
+        lockout: Duration,
+    },
+    /// Makes the caster's next spell with a cast time instant.
+    NextCastInstant,
+    /// Removes up to `count` auras of the given dispel type from every target.
+    /// Cleanses remove `harmful` auras, purges helpful ones.
+    Dispel {
+        dispel: DispelType,
+        #[serde(default)]
+        harmful: bool,
+        count: u32,
+    },
+}
+
+/// The next spell with a cast time is cast instantly, see [`SpellEffect::NextCastInstant`].
+#[derive(Component)]
+pub struct InstantCast;
+
+/// Parameters for spells that travel as a projectile.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProjectileDefinition {
+    pub damage: u32,
+    pub speed: f32,
+    pub radius: f32,
+    /// How long the projectile lives for, in seconds.
+    #[serde(deserialize_with = "duration_from_secs")]
+    pub lifetime: Duration,
+    #[serde(default = "default_true")]
+    pub despawn_after_hit: bool,
+}
+
+/// An area on the ground that damages everything standing in it every `tick_interval`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GroundEffectDefinition {
+    pub radius: f32,
+    pub damage: u32,
+    #[serde(deserialize_with = "duration_from_secs")]
+    pub tick_interval: Duration,
+    #[serde(deserialize_with = "duration_from_secs")]
+    pub duration: Duration,
+}
+
+/// Everything needed to resolve a spell's effects against its caster and targets.
+/// Corpses are left out: they can neither cast nor be affected.
+#[derive(SystemParam)]
+pub struct SpellEffectExecutor<'w, 's> {
+    commands: Commands<'w, 's>,
+    meshes: ResMut<'w, Assets<Mesh>>,
+    materials: ResMut<'w, Assets<StandardMaterial>>,
+    interrupt_events: EventWriter<'w, InterruptCast>,
+    aura_applied_events: EventWriter<'w, AuraApplied>,
+    damage_requests: EventWriter<'w, DamageRequest>,
+    heal_requests: EventWriter<'w, HealRequest>,
+    units: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static mut Transform,
+            Option<&'static mut Health>,
+            &'static mut Auras,
+        ),
+        Without<Dead>,
+    >,
+}
+
+impl<'w, 's> SpellEffectExecutor<'w, 's> {
+    /// Every unit the spell affects, according to its [`TargetingMode`].
+    pub fn resolve_targets(
+        &self,
+        caster: Entity,
+        spell: &SpellDefinition,
+        target: CastTarget,
+    ) -> Vec<Entity> {
+        let Ok((_, caster_transform, ..)) = self.units.get(caster) else {
+            return vec![];
+        };
+
+        match (spell.targeting, target) {
+            (TargetingMode::Caster, _) => vec![caster],
+            (TargetingMode::Unit, CastTarget::Unit(entity)) => match self.units.get(entity) {
+                Ok((_, _, Some(_), ..)) => vec![entity],
+                _ => vec![],
+            },
+            (TargetingMode::Unit, _) => vec![],
+            (targeting, target) => {
+                let point = self.target_position(target);
+
+                self.units
+                    .iter()
+                    .filter(|(entity, transform, health, ..)| {
+                        *entity != caster
+                            && health.is_some()
+                            && targeting.covers(
+                                spell.range,
+                                caster_transform,
+                                point,
+                                transform.translation,
+                            )
+                    })
+                    .map(|(entity, ..)| entity)
+                    .collect()
+            }
+        }
+    }
+
+    /// Every unit with health within `radius` of `center` (itself included), along the xz plane.
+    pub fn units_around(&self, center: Entity, radius: f32) -> Vec<Entity> {
+        let Ok((_, center, ..)) = self.units.get(center) else {
+            return vec![];
+        };
+
+        self.units
+            .iter()
+            .filter(|(_, transform, health, ..)| {
+                health.is_some() && xz_distance(center.translation, transform.translation) <= radius
+            })
+            .map(|(entity, ..)| entity)
+            .collect()
+    }
+
+    fn target_position(&self, target: CastTarget) -> Option<Vec3> {
+        match target {
+            CastTarget::None => None,
+            CastTarget::Unit(entity) => self
+                .units
+                .get(entity)
+                .ok()
+                .map(|(_, transform, ..)| transform.translation),
+            CastTarget::Point(point) => Some(point),
+        }
+    }
+
+    pub fn execute(
+        &mut self,
+        caster: Entity,
+        spell: &SpellDefinition,
+        target: CastTarget,
+        targets: &[Entity],
+    ) {
+        self.apply(
+            caster,
+            &spell.id,
+            &spell.name,
+            spell.school,
+            &spell.effects,
+            target,
+            targets,
+        );
+    }
+
+    /// Resolves `effects` on behalf of `caster`, e.g. for a spell or a trigger named `name`.
+    /// Their damage is of `school`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn apply(
+        &mut self,
+        caster: Entity,
+        spell_id: &SpellId,
+        name: &str,
+        school: SpellSchool,
+        effects: &[SpellEffect],
+        target: CastTarget,
+        targets: &[Entity],
+    ) {
+        let Ok((_, caster_transform, _, caster_auras)) = self.units.get(caster) else {
+            return;
+        };
+
+        let caster_transform = *caster_transform;
+        let caster_modifiers = caster_auras.caster_modifiers();
+        let target_position = self.target_position(target);
+
+        for effect in effects.iter() {
+            match effect {
+                SpellEffect::DirectDamage { amount } => {
+                    for target in targets {
+                        if let Ok((entity, _, Some(_), ..)) = self.units.get(*target) {
+                            self.damage_requests.send(DamageRequest::new(
+                                Some(caster),
+                                entity,
+                                spell_id,
+                                school,
+                                *amount,
+                            ));
+                        }
+                    }
+                }
+                SpellEffect::Heal { amount } => {
+                    for target in targets {
+                        if let Ok((entity, _, Some(_), ..)) = self.units.get(*target) {
+                            self.heal_requests.send(HealRequest::new(
+                                Some(caster),
+                                entity,
+                                spell_id,
+                                *amount,
+                            ));
+                        }
+                    }
+                }
+                SpellEffect::SpawnProjectile(projectile) => {
+                    // aimed at the target if there is one, straight ahead otherwise
+                    let direction = target_position
+                        .map(|position| {
+                            (position - caster_transform.translation).normalize_or_zero()
+                        })
+                        .unwrap_or(caster_transform.forward());
+
+                    self.spawn_projectile(
+                        caster,
+                        &caster_transform,
+                        direction,
+                        spell_id,
+                        name,
+                        school,
+                        projectile,
+                    )
+                }
+                SpellEffect::ApplyAura(aura) => {
+                    for target in targets {
+                        if let Ok((entity, _, _, mut auras)) = self.units.get_mut(*target) {
+                            apply_aura(
+                                entity,
+                                AuraInstance::new(aura, Some(caster), spell_id.clone(), school)
+                                    .with_caster(caster_modifiers),
+                                &mut auras,
+                                &mut self.aura_applied_events,
+                            );
+                        }
+                    }
+                }
+                SpellEffect::Knockback { distance } => {
+                    for target in targets {
+                        if let Ok((_, mut transform, ..)) = self.units.get_mut(*target) {
+                            let away = transform.translation - caster_transform.translation;
+                            let direction = Vec3::new(away.x, 0.0, away.z).normalize_or_zero();
+
+                            transform.translation += direction * *distance;
+                        }
+                    }
+                }
+                SpellEffect::Teleport { distance } => {
+                    if let Ok((_, mut transform, ..)) = self.units.get_mut(caster) {
+                        let forward = transform.forward();
+
+                        transform.translation += forward * *distance;
+                    }
+                }
+                SpellEffect::SpawnGroundEffect(ground_effect) => {
+                    let position = target_position.unwrap_or(caster_transform.translation);
+
+                    self.spawn_ground_effect(caster, spell_id, school, position, ground_effect)
+                }
+                SpellEffect::Interrupt { lockout } => {
+                    for target in targets {
+                        self.interrupt_events.send(InterruptCast {
+                            target: *target,
+                            lockout: *lockout,
+                        });
+                    }
+                }
+                SpellEffect::NextCastInstant => {
+                    self.commands.entity(caster).insert(InstantCast);
+                }
+                SpellEffect::Dispel {
+                    dispel,
+                    harmful,
+                    count,
+                } => {
+                    for target in targets {
+                        if let Ok((entity, _, _, mut auras)) = self.units.get_mut(*target) {
+                            let dispelled = auras.dispel(*dispel, *harmful, *count);
+
+                            debug!(
+                                "{:?} dispelled {} auras from {:?}",
+                                caster, dispelled, entity
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn spawn_projectile(
+        &mut self,
+        caster: Entity,
+        origin: &Transform,
+        direction: Vec3,
+        spell_id: &SpellId,
+        name: &str,
+        school: SpellSchool,
+        projectile: &ProjectileDefinition,
+    ) {
+        self.commands
+            .spawn(PbrBundle {
+                mesh: self.meshes.add(Mesh::from(shape::UVSphere {
+                    radius: projectile.radius,
+                    stacks: 18,
+                    sectors: 36,
+                })),
+                material: self.materials.add(Color::BLACK.into()),
+                transform: Transform::from_translation(origin.translation),
+                ..default()
+            })
+            .insert(Lifetime {
+                timer: Timer::new(projectile.lifetime, TimerMode::Once),
+            })
+            .insert(Name::new(name.to_string()))
+            .insert(Damage {
+                amount: projectile.damage,
+                school,
+            })
+            .insert(Projectile {
+                source: Some(caster),
+                spell_id: spell_id.clone(),
+                despawn_after_hit: projectile.despawn_after_hit,
+                speed: projectile.speed,
+                direction,
+            });
+    }
+
+    fn spawn_ground_effect(
+        &mut self,
+        caster: Entity,
+        spell_id: &SpellId,
+        school: SpellSchool,
+        position: Vec3,
+        ground_effect: &GroundEffectDefinition,
+    ) {
+        self.commands.spawn((
+            TransformBundle::from_transform(Transform::from_translation(position)),
+            GroundEffect {
+                source: caster,
+                spell_id: spell_id.clone(),
+                school,
+                radius: ground_effect.radius,
+                damage: ground_effect.damage,
+                tick: Timer::new(ground_effect.tick_interval, TimerMode::Repeating),
+            },
+            Lifetime {
+                timer: Timer::new(ground_effect.duration, TimerMode::Once),
+            },
+            Name::new("Ground Effect"),
+        ));
+    }
+}
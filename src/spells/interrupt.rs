@@ -0,0 +1,196 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    auras::Auras, character_controller::Player, controller::MovementAction,
+    crowd_control::CrowdControl, damage::DamageDealt,
+};
+
+use super::{Casting, Cooldowns, SpellId};
+
+/// How far a movement input has to go before it counts as moving, so that e.g. a centred
+/// gamepad stick doesn't cancel every cast.
+const MOVEMENT_DEADZONE: f32 = 0.1;
+
+/// Rules that decide what happens to a cast in progress.
+#[derive(Resource)]
+pub struct CastRules {
+    /// Whether moving cancels casts of spells that don't allow casting while moving.
+    pub moving_cancels_cast: bool,
+    /// How far a cast is set back every time the caster takes damage.
+    pub pushback: Duration,
+    /// How many times a single cast can be pushed back.
+    pub max_pushbacks: u32,
+    /// Spells pressed this close to the end of a cast or cooldown are queued instead of dropped.
+    pub queue_window: Duration,
+}
+
+impl Default for CastRules {
+    fn default() -> Self {
+        CastRules {
+            moving_cancels_cast: true,
+            pushback: Duration::from_millis(500),
+            max_pushbacks: 2,
+            queue_window: Duration::from_millis(400),
+        }
+    }
+}
+
+/// Cancels the target's cast (if any) and locks out its spell school.
+#[derive(Event)]
+pub struct InterruptCast {
+    pub target: Entity,
+    pub lockout: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastInterruptReason {
+    Moved,
+    PushedBack,
+    Interrupted,
+    CrowdControlled(CrowdControl),
+}
+
+impl CastInterruptReason {
+    /// Pushback only delays the cast, everything else cancels it.
+    pub fn cancels_cast(&self) -> bool {
+        !matches!(self, CastInterruptReason::PushedBack)
+    }
+}
+
+#[derive(Event)]
+pub struct CastInterrupted {
+    pub caster: Entity,
+    pub spell_id: SpellId,
+    pub reason: CastInterruptReason,
+}
+
+pub fn cancel_cast_on_movement_system(
+    mut movement_events: EventReader<MovementAction>,
+    casters: Query<(Entity, &Casting, Option<&Auras>), With<Player>>,
+    cast_rules: Res<CastRules>,
+    mut cast_interrupted_events: EventWriter<CastInterrupted>,
+    mut commands: Commands,
+) {
+    // movement actions are only ever sent for the player's character
+    let moved = movement_events
+        .read()
+        .filter(|action| match action {
+            MovementAction::Move(direction) => direction.length() > MOVEMENT_DEADZONE,
+            MovementAction::Jump => true,
+        })
+        .count()
+        > 0;
+
+    if !moved || !cast_rules.moving_cancels_cast {
+        return;
+    }
+
+    for (entity, casting, auras) in casters.iter() {
+        if casting.cast_while_moving {
+            continue;
+        }
+
+        // a caster that can't move doesn't move by pressing the keys
+        if auras.is_some_and(|auras| {
+            auras
+                .crowd_controlled_by(CrowdControl::blocks_movement)
+                .is_some()
+        }) {
+            continue;
+        }
+
+        commands.entity(entity).remove::<Casting>();
+
+        cast_interrupted_events.send(CastInterrupted {
+            caster: entity,
+            spell_id: casting.spell_id.clone(),
+            reason: CastInterruptReason::Moved,
+        });
+    }
+}
+
+/// Cancels the cast of anyone that gets stunned, silenced or feared mid-cast.
+pub fn cancel_cast_on_crowd_control_system(
+    casters: Query<(Entity, &Casting, &Auras)>,
+    mut cast_interrupted_events: EventWriter<CastInterrupted>,
+    mut commands: Commands,
+) {
+    for (entity, casting, auras) in casters.iter() {
+        let Some(crowd_control) = auras.crowd_controlled_by(CrowdControl::blocks_casting) else {
+            continue;
+        };
+
+        commands.entity(entity).remove::<Casting>();
+
+        cast_interrupted_events.send(CastInterrupted {
+            caster: entity,
+            spell_id: casting.spell_id.clone(),
+            reason: CastInterruptReason::CrowdControlled(crowd_control),
+        });
+    }
+}
+
+pub fn pushback_system(
+    mut damage_dealt_events: EventReader<DamageDealt>,
+    mut casters: Query<&mut Casting>,
+    cast_rules: Res<CastRules>,
+    mut cast_interrupted_events: EventWriter<CastInterrupted>,
+) {
+    for event in damage_dealt_events.read() {
+        // fully absorbed damage doesn't push the cast back
+        if event.amount == 0 {
+            continue;
+        }
+
+        let Ok(mut casting) = casters.get_mut(event.target) else {
+            continue;
+        };
+
+        if casting.pushbacks >= cast_rules.max_pushbacks {
+            continue;
+        }
+
+        casting.pushbacks += 1;
+
+        // channels lose time off the end instead of being set back
+        if casting.is_channel() {
+            casting.current_duration =
+                (casting.current_duration + cast_rules.pushback).min(casting.total_duration);
+        } else {
+            casting.current_duration = casting.current_duration.saturating_sub(cast_rules.pushback);
+        }
+
+        cast_interrupted_events.send(CastInterrupted {
+            caster: event.target,
+            spell_id: casting.spell_id.clone(),
+            reason: CastInterruptReason::PushedBack,
+        });
+    }
+}
+
+pub fn interrupt_cast_system(
+    mut interrupt_events: EventReader<InterruptCast>,
+    mut casters: Query<(&Casting, Option<&mut Cooldowns>)>,
+    mut cast_interrupted_events: EventWriter<CastInterrupted>,
+    mut commands: Commands,
+) {
+    for event in interrupt_events.read() {
+        let Ok((casting, cooldowns)) = casters.get_mut(event.target) else {
+            continue;
+        };
+
+        if let Some(mut cooldowns) = cooldowns {
+            cooldowns.lock_school(casting.school, event.lockout);
+        }
+
+        commands.entity(event.target).remove::<Casting>();
+
+        cast_interrupted_events.send(CastInterrupted {
+            caster: event.target,
+            spell_id: casting.spell_id.clone(),
+            reason: CastInterruptReason::Interrupted,
+        });
+    }
+}
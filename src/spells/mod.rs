@@ -1,9 +1,23 @@
 mod casting;
+mod cooldown;
+mod definition;
+mod effects;
+mod interrupt;
 mod model;
 mod plugin;
+mod queue;
 mod spell_system;
+mod targeting;
+mod triggers;
 
 pub use casting::*;
+pub use cooldown::*;
+pub use definition::*;
+pub use effects::*;
+pub use interrupt::*;
 pub use model::*;
 pub use plugin::*;
+pub use queue::*;
 pub use spell_system::*;
+pub use targeting::*;
+pub use triggers::*;
@@ -1,22 +1,54 @@
 use std::time::Duration;
 
-use bevy::ecs::event::Event;
+use bevy::ecs::{entity::Entity, event::Event};
+use serde::Deserialize;
 
-use crate::auras::Aura;
+use crate::crowd_control::CrowdControl;
 
-#[derive(Event)]
+use super::{duration_from_secs, CastTarget, SpellId};
+
+/// The school of magic a spell belongs to. Interrupts lock out a whole school.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
+pub enum SpellSchool {
+    #[default]
+    Physical,
+    Arcane,
+    Fire,
+    Frost,
+    Nature,
+    Shadow,
+    Holy,
+}
+
+#[derive(Event, Debug, Clone, Copy, Deserialize)]
 pub enum CastTime {
     Instant,
-    Duration(Duration),
+    Duration(#[serde(deserialize_with = "duration_from_secs")] Duration),
+    /// Fires the spell every `tick_interval` for as long as the channel lasts.
+    Channeled {
+        #[serde(deserialize_with = "duration_from_secs")]
+        duration: Duration,
+        #[serde(deserialize_with = "duration_from_secs")]
+        tick_interval: Duration,
+    },
+}
+
+/// Spells with charges can be cast `max` times in a row,
+/// with each charge coming back one at a time after `recharge`.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Charges {
+    pub max: u32,
+    #[serde(deserialize_with = "duration_from_secs")]
+    pub recharge: Duration,
 }
 
 /// Struct representing a spell initialization cast.
+/// Everything else about the spell is resolved from the `SpellBook`.
 #[derive(Event)]
 pub struct CastSpellInit {
-    pub spell_id: String,
-    pub cast_time: CastTime,
-    pub damage: u32,
-    pub apply_auras: Vec<Aura>,
+    pub spell_id: SpellId,
+    /// Only set when the target was picked up front, e.g. a point on the ground.
+    pub target: CastTarget,
 }
 
 /// Event that represents the firing of a spell:
@@ -24,5 +56,31 @@ pub struct CastSpellInit {
 /// is capable of doing it's primary stuff
 #[derive(Event)]
 pub struct CastSpellFire {
-    pub id: String,
+    pub caster: Entity,
+    pub id: SpellId,
+    pub target: CastTarget,
+    /// Set for every periodic fire of a channeled spell.
+    pub channel_tick: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastFailedReason {
+    OnCooldown,
+    NotEnoughResource,
+    SchoolLocked,
+    NoTarget,
+    OutOfRange,
+    NotFacing,
+    CrowdControlled(CrowdControl),
+    Dead,
+    /// Busy with another cast for longer than the queue window.
+    AlreadyCasting,
+}
+
+/// Sent when a [`CastSpellInit`] is rejected.
+#[derive(Event)]
+pub struct CastFailed {
+    pub caster: Entity,
+    pub spell_id: SpellId,
+    pub reason: CastFailedReason,
 }
@@ -1,17 +1,56 @@
-use bevy::app::{Plugin, Update};
+use bevy::{
+    app::{Plugin, Update},
+    asset::AssetApp,
+};
 
 use super::{
     casting::casting_system,
-    model::{CastSpellFire, CastSpellInit},
+    cooldown::{cooldown_system, start_cooldown_system},
+    definition::{spell_book_system, SpellBook, SpellDefinition, SpellDefinitionLoader},
+    interrupt::{
+        cancel_cast_on_crowd_control_system, cancel_cast_on_movement_system, interrupt_cast_system,
+        pushback_system, CastInterrupted, CastRules, InterruptCast,
+    },
+    model::{CastFailed, CastSpellFire, CastSpellInit},
+    queue::spell_queue_system,
     spell_init_system, spell_system,
+    targeting::{current_target_system, tab_target_system},
+    triggers::trigger_system,
 };
 
 pub struct SpellsPlugin;
 
 impl Plugin for SpellsPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_asset::<SpellDefinition>();
+        app.init_asset_loader::<SpellDefinitionLoader>();
+        app.init_resource::<SpellBook>();
+        app.init_resource::<CastRules>();
         app.add_event::<CastSpellInit>();
         app.add_event::<CastSpellFire>();
-        app.add_systems(Update, (spell_system, casting_system, spell_init_system));
+        app.add_event::<CastFailed>();
+        app.add_event::<InterruptCast>();
+        app.add_event::<CastInterrupted>();
+        app.add_systems(
+            Update,
+            (
+                spell_book_system,
+                spell_system,
+                casting_system,
+                spell_init_system,
+                cooldown_system,
+                start_cooldown_system,
+                (
+                    cancel_cast_on_movement_system,
+                    cancel_cast_on_crowd_control_system,
+                    pushback_system,
+                    interrupt_cast_system,
+                ),
+                tab_target_system,
+                current_target_system,
+                trigger_system,
+                spell_queue_system,
+            ),
+        );
     }
 }
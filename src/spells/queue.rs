@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::character_controller::Player;
+
+use super::{CastSpellInit, CastTarget, Casting, Cooldowns, SpellId};
+
+/// A spell that was pressed shortly before its caster was free to cast it.
+/// It is cast as soon as the caster is.
+#[derive(Component)]
+pub struct QueuedSpell {
+    pub spell_id: SpellId,
+    pub target: CastTarget,
+}
+
+/// How long until the caster is free to cast the spell,
+/// i.e. the longest of the rest of the current cast and the cooldown blocking the spell.
+pub fn busy_for(cooldowns: &Cooldowns, casting: Option<&Casting>, spell_id: &SpellId) -> Duration {
+    let cooldown = cooldowns
+        .blocking(spell_id)
+        .map_or(Duration::ZERO, |timer| timer.remaining());
+
+    let cast = casting.map_or(Duration::ZERO, |casting| {
+        casting
+            .total_duration
+            .saturating_sub(casting.current_duration)
+    });
+
+    cooldown.max(cast)
+}
+
+pub fn spell_queue_system(
+    casters: Query<(Entity, &QueuedSpell, &Cooldowns, Option<&Casting>), With<Player>>,
+    mut cast_spell_init_events: EventWriter<CastSpellInit>,
+    mut commands: Commands,
+) {
+    for (entity, queued, cooldowns, casting) in &casters {
+        if !busy_for(cooldowns, casting, &queued.spell_id).is_zero() {
+            continue;
+        }
+
+        commands.entity(entity).remove::<QueuedSpell>();
+
+        cast_spell_init_events.send(CastSpellInit {
+            spell_id: queued.spell_id.clone(),
+            target: queued.target,
+        });
+    }
+}
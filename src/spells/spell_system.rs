@@ -1,156 +1,208 @@
-use std::{cmp::min, time::Duration};
-
-use bevy::prelude::*;
+use bevy::{ecs::query::Has, prelude::*};
 
 use crate::{
-    auras::{apply_overtime, Overtime, OvertimeComponent},
-    character_controller::Player,
-    damage::Damage,
-    damage_text::spawn_damage_text_on_entity,
-    health::Health,
-    lifetime::Lifetime,
-    projectile::Projectile,
-    utils,
+    aoe::GroundTargetInitEvent, auras::Auras, character_controller::Player,
+    crowd_control::CrowdControl, death::Dead, health::Health, resource_pool::ResourcePool,
 };
 
-use super::{casting::Casting, model::CastSpellFire, CastSpellInit, CastTime};
+use super::{
+    busy_for, casting::Casting, model::CastSpellFire, CastFailed, CastFailedReason, CastRules,
+    CastSpellInit, CastTarget, CastTime, Cooldowns, CurrentTarget, InstantCast, QueuedSpell,
+    SpellBook, SpellDefinition, SpellEffectExecutor, TargetingMode, GLOBAL_COOLDOWN,
+};
 
+#[allow(clippy::too_many_arguments)]
 pub fn spell_init_system(
     mut cast_spell_init_events: EventReader<CastSpellInit>,
     mut cast_spell_fire_events: EventWriter<CastSpellFire>,
-    mut player_query: Query<Entity, With<Player>>,
+    mut cast_failed_events: EventWriter<CastFailed>,
+    mut ground_target_events: EventWriter<GroundTargetInitEvent>,
+    mut player_query: Query<
+        (
+            Entity,
+            &Transform,
+            &CurrentTarget,
+            &mut Cooldowns,
+            Option<&mut ResourcePool>,
+            Has<InstantCast>,
+            Option<&Casting>,
+            Option<&Auras>,
+            Has<Dead>,
+        ),
+        With<Player>,
+    >,
+    units: Query<&Transform, With<Health>>,
+    spell_book: Res<SpellBook>,
+    cast_rules: Res<CastRules>,
+    definitions: Res<Assets<SpellDefinition>>,
     mut commands: Commands,
 ) {
     for event in &mut cast_spell_init_events.read() {
-        match event.cast_time {
-            CastTime::Instant => cast_spell_fire_events.send(CastSpellFire {
-                id: event.spell_id.to_string(),
-            }),
-            CastTime::Duration(duration) => {
-                let player = player_query.single_mut();
+        let Some(spell) = spell_book.get(&event.spell_id, &definitions) else {
+            warn!("Unknown spell {:?}", event.spell_id);
+            continue;
+        };
+
+        let Ok((
+            player,
+            transform,
+            current_target,
+            mut cooldowns,
+            mut pool,
+            instant_cast,
+            casting,
+            auras,
+            dead,
+        )) = player_query.get_single_mut()
+        else {
+            continue;
+        };
+
+        let crowd_controlled_by =
+            auras.and_then(|auras| auras.crowd_controlled_by(CrowdControl::blocks_casting));
+
+        let busy_for = busy_for(&cooldowns, casting, &event.spell_id);
+
+        if !busy_for.is_zero() && busy_for <= cast_rules.queue_window {
+            commands.entity(player).insert(QueuedSpell {
+                spell_id: event.spell_id.clone(),
+                target: event.target,
+            });
+            continue;
+        }
+
+        // too early to be queued, and starting another cast would drop the current one
+        if casting.is_some() {
+            cast_failed_events.send(CastFailed {
+                caster: player,
+                spell_id: event.spell_id.clone(),
+                reason: CastFailedReason::AlreadyCasting,
+            });
+            continue;
+        }
 
-                commands.entity(player).insert(Casting {
-                    spell_id: event.spell_id.to_string(),
-                    current_duration: Duration::ZERO,
-                    total_duration: duration,
-                });
+        let target = match spell.targeting {
+            TargetingMode::Unit => current_target.0.map_or(CastTarget::None, CastTarget::Unit),
+            TargetingMode::Ground { .. } => event.target,
+            TargetingMode::Caster | TargetingMode::Cone { .. } | TargetingMode::Line { .. } => {
+                CastTarget::None
             }
+        };
+
+        let failure = if dead {
+            Err(CastFailedReason::Dead)
+        } else if let Some(crowd_control) = crowd_controlled_by {
+            Err(CastFailedReason::CrowdControlled(crowd_control))
+        } else if cooldowns.is_school_locked(spell.school) {
+            Err(CastFailedReason::SchoolLocked)
+        } else if !cooldowns.is_ready(&event.spell_id) {
+            Err(CastFailedReason::OnCooldown)
+        } else if !can_afford(spell, pool.as_deref()) {
+            Err(CastFailedReason::NotEnoughResource)
+        } else if matches!(spell.targeting, TargetingMode::Ground { .. })
+            && target == CastTarget::None
+        {
+            // the spell is cast again with the point once the player has picked one
+            ground_target_events.send(GroundTargetInitEvent {
+                spell_id: event.spell_id.clone(),
+            });
+            continue;
+        } else {
+            spell
+                .targeting
+                .validate(spell.range, transform, target.position(&units))
+        };
+
+        if let Err(reason) = failure {
+            cast_failed_events.send(CastFailed {
+                caster: player,
+                spell_id: event.spell_id.clone(),
+                reason,
+            });
+            continue;
         }
-    }
-}
 
-pub fn spell_system(
-    mut cast_spell_fire_events: EventReader<CastSpellFire>,
-    character_query: Query<&Transform, With<Player>>,
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+        if spell.triggers_global_cooldown {
+            cooldowns.start_global(GLOBAL_COOLDOWN);
+        }
 
-    // This is a big query right now
-    mut other_entities: Query<
-        (
-            Entity,
-            &Transform,
-            &mut Health,
-            Option<&mut OvertimeComponent>,
-        ),
-        (With<Health>, Without<Player>),
-    >,
-) {
-    for event in &mut cast_spell_fire_events.read() {
-        match event.id.as_str() {
-            "s" => {
-                let character = character_query.single();
-                cast_spell(character, &mut commands, &mut meshes, &mut materials)
+        let haste = auras.map_or(1.0, |auras| auras.haste());
+
+        let cast_time = match spell.cast_time {
+            CastTime::Duration(_) if instant_cast => {
+                commands.entity(player).remove::<InstantCast>();
+                CastTime::Instant
+            }
+            cast_time => cast_time,
+        };
+
+        match cast_time {
+            CastTime::Instant => {
+                // the cost is paid on fire, which for instants is right now
+                if let (Some(cost), Some(pool)) = (&spell.cost, pool.as_deref_mut()) {
+                    pool.spend(cost);
+                }
+
+                cast_spell_fire_events.send(CastSpellFire {
+                    caster: player,
+                    id: event.spell_id.clone(),
+                    target,
+                    channel_tick: false,
+                })
+            }
+            CastTime::Duration(duration) => {
+                commands.entity(player).insert(Casting::new(
+                    event.spell_id.clone(),
+                    target,
+                    spell,
+                    duration.div_f32(haste),
+                ));
             }
-            "a" => {
-                let character = character_query.single();
-                basic_attack(character, &mut commands, &mut other_entities)
+            CastTime::Channeled {
+                duration,
+                tick_interval,
+            } => {
+                // channels pay and go on cooldown as soon as they start
+                if let (Some(cost), Some(pool)) = (&spell.cost, pool.as_deref_mut()) {
+                    pool.spend(cost);
+                }
+
+                cooldowns.start(event.spell_id.clone(), spell);
+
+                commands.entity(player).insert(Casting::channel(
+                    event.spell_id.clone(),
+                    target,
+                    spell,
+                    duration.div_f32(haste),
+                    tick_interval.div_f32(haste),
+                ));
             }
-            _ => {}
         }
     }
 }
 
-fn cast_spell(
-    character: &Transform,
-    commands: &mut Commands,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &mut ResMut<Assets<StandardMaterial>>,
-    // effects: &mut ResMut<Assets<EffectAsset>>
-) {
-    let _direction = character.forward();
-
-    // let portal = make_particle(commands, effects);
-
-    commands
-        .spawn(
-            // ParticleEffectBundle {
-            //     effect: ParticleEffect::new(portal),
-            //     transform: Transform::IDENTITY,
-            //     ..Default::default()
-            // },
-            PbrBundle {
-                mesh: meshes.add(Mesh::from(shape::UVSphere {
-                    radius: 0.2,
-                    stacks: 18,
-                    sectors: 36,
-                })),
-                material: materials.add(Color::BLACK.into()),
-                transform: Transform::from_translation(character.translation),
-                ..default()
-            },
-        )
-        .insert(Lifetime {
-            timer: Timer::from_seconds(1.0, TimerMode::Once),
-        })
-        .insert(Name::new("Bullet"))
-        .insert(Damage { amount: 10 })
-        .insert(Projectile {
-            despawn_after_hit: true,
-            speed: 1.0,
-            direction: character.forward()
-        });
+/// Whether the caster's pool can pay for the spell. Spells without a cost are always affordable.
+pub fn can_afford(spell: &SpellDefinition, pool: Option<&ResourcePool>) -> bool {
+    match (&spell.cost, pool) {
+        (None, _) => true,
+        (Some(cost), Some(pool)) => pool.can_afford(cost),
+        (Some(_), None) => false,
+    }
 }
 
-fn basic_attack(
-    // buttons: Res<Input<KeyCode>>,
-    player: &Transform,
-    commands: &mut Commands,
-    other_entities: &mut Query<
-        (
-            Entity,
-            &Transform,
-            &mut Health,
-            Option<&mut OvertimeComponent>,
-        ),
-        (With<Health>, Without<Player>),
-    >,
+pub fn spell_system(
+    mut cast_spell_fire_events: EventReader<CastSpellFire>,
+    spell_book: Res<SpellBook>,
+    definitions: Res<Assets<SpellDefinition>>,
+    mut executor: SpellEffectExecutor,
 ) {
-    let player = player.translation;
-
-    for (entity, transform, mut health, mut overtime_comp) in other_entities.iter_mut() {
-        // check if the entity is within radius
-        let distance = ((utils::safe_minus(transform.translation.z, player.z)).powi(2)
-            + (utils::safe_minus(transform.translation.x, player.x)).powi(2))
-        .sqrt();
-
-        // this just takes into account distance along the xz plane
-        if distance < 2.0 {
-            let amount = 10;
-
-            spawn_damage_text_on_entity(commands, entity, amount);
+    for event in &mut cast_spell_fire_events.read() {
+        let Some(spell) = spell_book.get(&event.id, &definitions) else {
+            continue;
+        };
 
-            health.current = health.current - min(amount, health.current);
+        let targets = executor.resolve_targets(event.caster, spell, event.target);
 
-            apply_overtime(
-                entity,
-                commands,
-                Overtime::damage_per_second(3, 5),
-                &mut overtime_comp,
-            );
-        }
+        executor.execute(event.caster, spell, event.target, &targets);
     }
-
-    // calculate front and sides
 }
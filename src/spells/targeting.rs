@@ -0,0 +1,187 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    character_controller::Player, death::Dead, enemy::Enemy, health::Health, utils::xz_distance,
+};
+
+use super::CastFailedReason;
+
+/// How a spell picks what it affects.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum TargetingMode {
+    /// Only ever affects the caster.
+    Caster,
+    /// The caster's [`CurrentTarget`], which has to be in range and in front of the caster.
+    Unit,
+    /// Every unit within `radius` of a point on the ground picked by the player.
+    Ground { radius: f32 },
+    /// Every unit in front of the caster, up to `angle` degrees wide and the spell's range long.
+    Cone { angle: f32 },
+    /// Every unit in a rectangle `width` wide, stretching the spell's range in front of the caster.
+    Line { width: f32 },
+}
+
+/// What a cast was aimed at, captured when the cast starts.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum CastTarget {
+    #[default]
+    None,
+    Unit(Entity),
+    Point(Vec3),
+}
+
+impl CastTarget {
+    /// Where the target currently is, if it still exists.
+    pub fn position(&self, units: &Query<&Transform, With<Health>>) -> Option<Vec3> {
+        match self {
+            CastTarget::None => None,
+            CastTarget::Unit(entity) => units
+                .get(*entity)
+                .ok()
+                .map(|transform| transform.translation),
+            CastTarget::Point(point) => Some(*point),
+        }
+    }
+}
+
+impl TargetingMode {
+    /// Checks that a target at `target` (if any) can be cast at from `caster`.
+    pub fn validate(
+        &self,
+        range: f32,
+        caster: &Transform,
+        target: Option<Vec3>,
+    ) -> Result<(), CastFailedReason> {
+        match self {
+            TargetingMode::Unit => {
+                let target = target.ok_or(CastFailedReason::NoTarget)?;
+
+                if xz_distance(caster.translation, target) > range {
+                    return Err(CastFailedReason::OutOfRange);
+                }
+
+                // anything within the half circle in front of the caster counts as faced
+                if flat_direction(caster.translation, target).dot(flat_forward(caster)) < 0.0 {
+                    return Err(CastFailedReason::NotFacing);
+                }
+
+                Ok(())
+            }
+            TargetingMode::Ground { .. } => {
+                let target = target.ok_or(CastFailedReason::NoTarget)?;
+
+                if xz_distance(caster.translation, target) > range {
+                    return Err(CastFailedReason::OutOfRange);
+                }
+
+                Ok(())
+            }
+            TargetingMode::Caster | TargetingMode::Cone { .. } | TargetingMode::Line { .. } => {
+                Ok(())
+            }
+        }
+    }
+
+    /// Whether a unit at `position` is inside the area of an area targeting mode.
+    /// Single target modes never cover anything, their target is picked directly.
+    pub fn covers(
+        &self,
+        range: f32,
+        caster: &Transform,
+        target: Option<Vec3>,
+        position: Vec3,
+    ) -> bool {
+        match self {
+            TargetingMode::Caster | TargetingMode::Unit => false,
+            TargetingMode::Ground { radius } => {
+                target.is_some_and(|target| xz_distance(target, position) <= *radius)
+            }
+            TargetingMode::Cone { angle } => {
+                let direction = flat_direction(caster.translation, position);
+
+                xz_distance(caster.translation, position) <= range
+                    && direction.angle_between(flat_forward(caster)).to_degrees() <= angle / 2.0
+            }
+            TargetingMode::Line { width } => {
+                let offset = position - caster.translation;
+                let forward = flat_forward(caster);
+                let along = offset.dot(forward);
+                let across = offset.dot(forward.cross(Vec3::Y));
+
+                (0.0..=range).contains(&along) && across.abs() <= width / 2.0
+            }
+        }
+    }
+}
+
+fn flat_forward(transform: &Transform) -> Vec3 {
+    let forward = transform.forward();
+
+    Vec3::new(forward.x, 0.0, forward.z).normalize_or_zero()
+}
+
+fn flat_direction(from: Vec3, to: Vec3) -> Vec3 {
+    Vec3::new(to.x - from.x, 0.0, to.z - from.z).normalize_or_zero()
+}
+
+/// The unit that the player has selected with tab targeting.
+#[derive(Component, Default)]
+pub struct CurrentTarget(pub Option<Entity>);
+
+/// Cycles through enemies from nearest to furthest.
+pub fn tab_target_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut player: Query<(&Transform, &mut CurrentTarget), With<Player>>,
+    enemies: Query<(Entity, &Transform), (With<Enemy>, With<Health>, Without<Dead>)>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Tab) {
+        return;
+    }
+
+    let Ok((player_transform, mut current_target)) = player.get_single_mut() else {
+        return;
+    };
+
+    let mut enemies: Vec<_> = enemies
+        .iter()
+        .map(|(entity, transform)| {
+            (
+                entity,
+                xz_distance(player_transform.translation, transform.translation),
+            )
+        })
+        .collect();
+
+    enemies.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+
+    let next = current_target
+        .0
+        .and_then(|current| enemies.iter().position(|(entity, _)| *entity == current))
+        .map(|index| (index + 1) % enemies.len())
+        .unwrap_or(0);
+
+    current_target.0 = enemies.get(next).map(|(entity, _)| *entity);
+}
+
+/// Marks the current target on the ground and drops it once it no longer exists.
+pub fn current_target_system(
+    mut gizmos: Gizmos,
+    mut player: Query<&mut CurrentTarget, With<Player>>,
+    units: Query<&Transform, (With<Health>, Without<Dead>)>,
+) {
+    let Ok(mut current_target) = player.get_single_mut() else {
+        return;
+    };
+
+    let Some(target) = current_target.0 else {
+        return;
+    };
+
+    match units.get(target) {
+        Ok(transform) => {
+            gizmos.circle(transform.translation, Vec3::Y, 1.0, Color::YELLOW);
+        }
+        Err(_) => current_target.0 = None,
+    }
+}
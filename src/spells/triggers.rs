@@ -0,0 +1,355 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    damage::{DamageDealt, HealApplied},
+    hit_table::HitOutcome,
+    rng::CombatRng,
+};
+
+use super::{CastSpellFire, CastTarget, SpellEffect, SpellEffectExecutor, SpellId, SpellSchool};
+
+/// What a trigger listens for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerEvent {
+    /// The owner fires a spell. Channel ticks don't count.
+    Cast,
+    /// The owner damages something.
+    Hit,
+    /// The owner critically hits something.
+    Crit,
+    /// The owner heals something.
+    Heal,
+    /// The owner takes damage.
+    DamageTaken,
+}
+
+/// Who a trigger's effects are applied to.
+#[derive(Debug, Clone, Copy)]
+pub enum TriggerTarget {
+    Owner,
+    /// The other side of the event, e.g. whatever was hit or whoever did the hitting.
+    Other,
+}
+
+/// A passive effect, e.g. "10% chance on hit to apply a DoT".
+#[derive(Debug, Clone)]
+pub struct TriggerDefinition {
+    pub name: String,
+    pub on: TriggerEvent,
+    /// Chance between 0 and 1 to go off once the trigger is due.
+    pub chance: f32,
+    /// Number of matching events it takes for the trigger to be due, e.g. every 3rd cast.
+    pub every: u32,
+    /// Time after going off during which the trigger ignores every event.
+    pub internal_cooldown: Duration,
+    pub target: TriggerTarget,
+    /// School of the trigger's damage, physical unless set otherwise.
+    pub school: SpellSchool,
+    pub effects: Vec<SpellEffect>,
+}
+
+impl TriggerDefinition {
+    pub fn new(name: &str, on: TriggerEvent, effects: Vec<SpellEffect>) -> Self {
+        TriggerDefinition {
+            name: name.to_string(),
+            on,
+            chance: 1.0,
+            every: 1,
+            internal_cooldown: Duration::ZERO,
+            target: TriggerTarget::Owner,
+            school: SpellSchool::default(),
+            effects,
+        }
+    }
+
+    pub fn with_chance(mut self, chance: f32) -> Self {
+        self.chance = chance;
+        self
+    }
+
+    pub fn every(mut self, every: u32) -> Self {
+        self.every = every;
+        self
+    }
+
+    pub fn with_internal_cooldown(mut self, internal_cooldown: Duration) -> Self {
+        self.internal_cooldown = internal_cooldown;
+        self
+    }
+
+    pub fn with_school(mut self, school: SpellSchool) -> Self {
+        self.school = school;
+        self
+    }
+
+    pub fn targeting_other(mut self) -> Self {
+        self.target = TriggerTarget::Other;
+        self
+    }
+}
+
+struct TriggerState {
+    definition: TriggerDefinition,
+    count: u32,
+    internal_cooldown: Option<Timer>,
+}
+
+impl TriggerState {
+    fn fire(&mut self, rng: &mut CombatRng) -> bool {
+        if self.internal_cooldown.is_some() {
+            return false;
+        }
+
+        self.count += 1;
+
+        if self.count < self.definition.every {
+            return false;
+        }
+
+        self.count = 0;
+
+        if !rng.roll(self.definition.chance) {
+            return false;
+        }
+
+        if !self.definition.internal_cooldown.is_zero() {
+            self.internal_cooldown = Some(Timer::new(
+                self.definition.internal_cooldown,
+                TimerMode::Once,
+            ));
+        }
+
+        true
+    }
+}
+
+/// Registry of the triggers a unit has, along with their counters and internal cooldowns.
+#[derive(Component)]
+pub struct Triggers {
+    triggers: Vec<TriggerState>,
+}
+
+impl Triggers {
+    pub fn new(definitions: Vec<TriggerDefinition>) -> Self {
+        Triggers {
+            triggers: definitions
+                .into_iter()
+                .map(|definition| TriggerState {
+                    definition,
+                    count: 0,
+                    internal_cooldown: None,
+                })
+                .collect(),
+        }
+    }
+
+    /// The definitions of every trigger that goes off for `event`.
+    fn fire(&mut self, event: TriggerEvent, rng: &mut CombatRng) -> Vec<TriggerDefinition> {
+        self.triggers
+            .iter_mut()
+            .filter(|trigger| trigger.definition.on == event)
+            .filter_map(|trigger| trigger.fire(rng).then(|| trigger.definition.clone()))
+            .collect()
+    }
+
+    fn tick(&mut self, delta: Duration) {
+        for trigger in self.triggers.iter_mut() {
+            let finished = trigger.internal_cooldown.as_mut().is_some_and(|timer| {
+                timer.tick(delta);
+                timer.finished()
+            });
+
+            if finished {
+                trigger.internal_cooldown = None;
+            }
+        }
+    }
+}
+
+/// Listens to cast, damage and heal events and applies the effects of the triggers they set off.
+/// Damage and healing done by a trigger's effects can set off further triggers.
+pub fn trigger_system(
+    time: Res<Time>,
+    mut rng: ResMut<CombatRng>,
+    mut cast_spell_fire_events: EventReader<CastSpellFire>,
+    mut damage_dealt_events: EventReader<DamageDealt>,
+    mut heal_applied_events: EventReader<HealApplied>,
+    mut owners: Query<&mut Triggers>,
+    mut executor: SpellEffectExecutor,
+) {
+    for mut triggers in &mut owners {
+        triggers.tick(time.delta());
+    }
+
+    // (owner, event, the other side of the event)
+    let mut events: Vec<(Entity, TriggerEvent, Option<Entity>)> = Vec::new();
+
+    for event in cast_spell_fire_events.read() {
+        if event.channel_tick {
+            continue;
+        }
+
+        let target = match event.target {
+            CastTarget::Unit(target) => Some(target),
+            _ => None,
+        };
+
+        events.push((event.caster, TriggerEvent::Cast, target));
+    }
+
+    for event in damage_dealt_events.read() {
+        // misses, dodges, parries and fully absorbed damage don't count as hits
+        if !event.outcome.lands() || event.amount == 0 {
+            continue;
+        }
+
+        // ticks of damage over time don't count as hits, or on hit effects would keep
+        // themselves going
+        if let Some(source) = event.source.filter(|_| !event.periodic) {
+            events.push((source, TriggerEvent::Hit, Some(event.target)));
+
+            if event.outcome == HitOutcome::Crit {
+                events.push((source, TriggerEvent::Crit, Some(event.target)));
+            }
+        }
+
+        events.push((event.target, TriggerEvent::DamageTaken, event.source));
+    }
+
+    for event in heal_applied_events.read() {
+        if let Some(source) = event.source {
+            events.push((source, TriggerEvent::Heal, Some(event.target)));
+        }
+    }
+
+    for (owner, event, other) in events {
+        let Ok(mut triggers) = owners.get_mut(owner) else {
+            continue;
+        };
+
+        for definition in triggers.fire(event, &mut rng) {
+            let target = match definition.target {
+                TriggerTarget::Owner => owner,
+                TriggerTarget::Other => match other {
+                    Some(other) => other,
+                    None => continue,
+                },
+            };
+
+            debug!("{:?} triggered {} on {:?}", owner, definition.name, target);
+
+            executor.apply(
+                owner,
+                &SpellId::new(&definition.name),
+                &definition.name,
+                definition.school,
+                &definition.effects,
+                CastTarget::Unit(target),
+                &[target],
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition(on: TriggerEvent) -> TriggerDefinition {
+        TriggerDefinition::new("Test", on, vec![SpellEffect::NextCastInstant])
+    }
+
+    fn fires(triggers: &mut Triggers, event: TriggerEvent, rng: &mut CombatRng) -> usize {
+        triggers.fire(event, rng).len()
+    }
+
+    #[test]
+    fn only_fires_for_its_event() {
+        let mut rng = CombatRng::seeded(1);
+        let mut triggers = Triggers::new(vec![definition(TriggerEvent::Cast)]);
+
+        assert_eq!(fires(&mut triggers, TriggerEvent::Hit, &mut rng), 0);
+        assert_eq!(fires(&mut triggers, TriggerEvent::Cast, &mut rng), 1);
+    }
+
+    #[test]
+    fn fires_on_every_nth_event() {
+        let mut rng = CombatRng::seeded(1);
+        let mut triggers = Triggers::new(vec![definition(TriggerEvent::Cast).every(3)]);
+
+        let fired: Vec<usize> = (0..6)
+            .map(|_| fires(&mut triggers, TriggerEvent::Cast, &mut rng))
+            .collect();
+
+        assert_eq!(fired, vec![0, 0, 1, 0, 0, 1]);
+    }
+
+    #[test]
+    fn chance_is_reproducible_with_a_seed() {
+        let run = |seed| {
+            let mut rng = CombatRng::seeded(seed);
+            let mut triggers = Triggers::new(vec![definition(TriggerEvent::Hit).with_chance(0.3)]);
+
+            (0..1000)
+                .map(|_| fires(&mut triggers, TriggerEvent::Hit, &mut rng))
+                .collect::<Vec<_>>()
+        };
+
+        let fired = run(42);
+
+        assert_eq!(fired, run(42));
+
+        // 30% of 1000 hits, give or take
+        let total: usize = fired.iter().sum();
+        assert!((200..400).contains(&total), "fired {} times", total);
+    }
+
+    #[test]
+    fn never_and_always() {
+        let mut rng = CombatRng::seeded(1);
+        let mut triggers = Triggers::new(vec![
+            definition(TriggerEvent::Hit).with_chance(0.0),
+            definition(TriggerEvent::Hit).with_chance(1.0),
+        ]);
+
+        for _ in 0..100 {
+            assert_eq!(fires(&mut triggers, TriggerEvent::Hit, &mut rng), 1);
+        }
+    }
+
+    #[test]
+    fn internal_cooldown_blocks_until_it_runs_out() {
+        let mut rng = CombatRng::seeded(1);
+        let mut triggers = Triggers::new(vec![
+            definition(TriggerEvent::Hit).with_internal_cooldown(Duration::from_secs(3))
+        ]);
+
+        assert_eq!(fires(&mut triggers, TriggerEvent::Hit, &mut rng), 1);
+        assert_eq!(fires(&mut triggers, TriggerEvent::Hit, &mut rng), 0);
+
+        triggers.tick(Duration::from_secs(2));
+        assert_eq!(fires(&mut triggers, TriggerEvent::Hit, &mut rng), 0);
+
+        triggers.tick(Duration::from_secs(1));
+        assert_eq!(fires(&mut triggers, TriggerEvent::Hit, &mut rng), 1);
+    }
+
+    #[test]
+    fn events_during_internal_cooldown_do_not_count() {
+        let mut rng = CombatRng::seeded(1);
+        let mut triggers = Triggers::new(vec![definition(TriggerEvent::Cast)
+            .every(2)
+            .with_internal_cooldown(Duration::from_secs(1))]);
+
+        assert_eq!(fires(&mut triggers, TriggerEvent::Cast, &mut rng), 0);
+        assert_eq!(fires(&mut triggers, TriggerEvent::Cast, &mut rng), 1);
+        assert_eq!(fires(&mut triggers, TriggerEvent::Cast, &mut rng), 0);
+
+        triggers.tick(Duration::from_secs(1));
+
+        assert_eq!(fires(&mut triggers, TriggerEvent::Cast, &mut rng), 0);
+        assert_eq!(fires(&mut triggers, TriggerEvent::Cast, &mut rng), 1);
+    }
+}
@@ -1,11 +1,35 @@
 use bevy::prelude::*;
 
+use crate::{
+    character_controller::Player,
+    keybindings::KEYBINDINGS,
+    spells::{Cooldowns, QueuedSpell, SpellBook, SpellDefinition, SpellId},
+};
+
 use super::{tooltip::ShowsTooltip, BUTTON_SIZE};
 
+/// An action bar button, bound to the spell that its key casts.
+#[derive(Component)]
+pub struct ActionBarButton {
+    pub spell_id: SpellId,
+}
+
+/// Overlay that shrinks as the button's cooldown runs out.
+#[derive(Component)]
+pub struct CooldownSweep;
+
+/// Remaining cooldown in seconds, shown on top of the button.
+#[derive(Component)]
+pub struct CooldownText;
+
+/// Charges left, for spells that have them.
+#[derive(Component)]
+pub struct ChargeText;
+
 fn spawn_action_bar_button(
     parent: &mut ChildBuilder,
     button: &str,
-    tooltip: ShowsTooltip,
+    spell_id: &str,
     asset_server: &Res<AssetServer>,
 ) {
     parent
@@ -15,14 +39,22 @@ fn spawn_action_bar_button(
                     width: Val::Px(BUTTON_SIZE),
                     height: Val::Px(BUTTON_SIZE),
                     margin: UiRect::all(Val::Px(1.0)),
+                    border: UiRect::all(Val::Px(1.0)),
                     justify_content: JustifyContent::Center,
                     align_items: AlignItems::Center,
                     ..Default::default()
                 },
                 background_color: Color::BLACK.into(),
+                border_color: Color::BLACK.into(),
                 ..default()
             },
-            tooltip,
+            ShowsTooltip {
+                title: String::new(),
+                description: String::new(),
+            },
+            ActionBarButton {
+                spell_id: SpellId::new(spell_id),
+            },
             Interaction::Hovered,
         ))
         .with_children(|parent| {
@@ -40,6 +72,70 @@ fn spawn_action_bar_button(
                 },
                 ..Default::default()
             });
+
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(0.0),
+                        bottom: Val::Px(0.0),
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(0.0),
+                        ..Default::default()
+                    },
+                    background_color: Color::rgba(0.5, 0.5, 0.5, 0.6).into(),
+                    ..Default::default()
+                },
+                CooldownSweep,
+            ));
+
+            parent.spawn((
+                TextBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(0.0),
+                        right: Val::Px(1.0),
+                        ..Default::default()
+                    },
+                    text: Text {
+                        sections: vec![TextSection {
+                            value: String::with_capacity(8),
+                            style: TextStyle {
+                                font: asset_server.load("Rosela.ttf"),
+                                font_size: 12.0,
+                                color: Color::YELLOW,
+                            },
+                        }],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                CooldownText,
+            ));
+
+            parent.spawn((
+                TextBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        bottom: Val::Px(0.0),
+                        right: Val::Px(1.0),
+                        ..Default::default()
+                    },
+                    text: Text {
+                        sections: vec![TextSection {
+                            value: String::with_capacity(4),
+                            style: TextStyle {
+                                font: asset_server.load("Rosela.ttf"),
+                                font_size: 12.0,
+                                color: Color::WHITE,
+                            },
+                        }],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                ChargeText,
+            ));
         });
 }
 
@@ -55,12 +151,115 @@ pub fn setup_action_bar(parent: &mut ChildBuilder, asset_server: &Res<AssetServe
             ..default()
         })
         .with_children(|parent| {
+            for (key, spell_id) in KEYBINDINGS {
+                spawn_action_bar_button(parent, &format!("{:?}", key), spell_id, asset_server);
+            }
 
-            spawn_action_bar_button(parent, "R", ShowsTooltip { title: "Attack".to_string(), description: "Attack the target, causing 100% weapon damage".to_string() }, asset_server);
+            // parent.spawn(ActionBarButton::default());
+        });
+}
 
-            spawn_action_bar_button(parent, "Q", ShowsTooltip { title: "Cast Spell".to_string(), description: "Cast a spell that moves outward from the caster, causing 100% spell damage to the first target it hits.".to_string() }, asset_server);
+/// Fills in the action bar tooltips from the spell definitions once they are loaded.
+pub fn update_action_bar_tooltips(
+    spell_book: Res<SpellBook>,
+    definitions: Res<Assets<SpellDefinition>>,
+    mut buttons: Query<(&ActionBarButton, &mut ShowsTooltip)>,
+) {
+    if !spell_book.is_changed() {
+        return;
+    }
 
+    for (button, mut tooltip) in &mut buttons {
+        if let Some(spell) = spell_book.get(&button.spell_id, &definitions) {
+            tooltip.title = spell.name.clone();
+            tooltip.description = spell.description.clone();
+        }
+    }
+}
 
-            // parent.spawn(ActionBarButton::default());
-        });
+pub fn update_action_bar_cooldowns(
+    player: Query<&Cooldowns, With<Player>>,
+    buttons: Query<&ActionBarButton>,
+    mut sweeps: Query<(&Parent, &mut Style), With<CooldownSweep>>,
+    mut texts: Query<(&Parent, &mut Text), With<CooldownText>>,
+) {
+    let Ok(cooldowns) = player.get_single() else {
+        return;
+    };
+
+    for (parent, mut style) in &mut sweeps {
+        let Ok(button) = buttons.get(parent.get()) else {
+            continue;
+        };
+
+        let percent_left = cooldowns
+            .blocking(&button.spell_id)
+            .map_or(0.0, |timer| timer.percent_left());
+
+        style.height = Val::Percent(percent_left * 100.0);
+    }
+
+    for (parent, mut text) in &mut texts {
+        let Ok(button) = buttons.get(parent.get()) else {
+            continue;
+        };
+
+        if let Some(section) = text.sections.get_mut(0) {
+            section.value.clear();
+
+            if let Some(timer) = cooldowns.blocking(&button.spell_id) {
+                section.value = format!("{:.1}", timer.remaining_secs());
+            }
+        }
+    }
+}
+
+pub fn update_action_bar_charges(
+    player: Query<&Cooldowns, With<Player>>,
+    spell_book: Res<SpellBook>,
+    definitions: Res<Assets<SpellDefinition>>,
+    buttons: Query<&ActionBarButton>,
+    mut texts: Query<(&Parent, &mut Text), With<ChargeText>>,
+) {
+    let Ok(cooldowns) = player.get_single() else {
+        return;
+    };
+
+    for (parent, mut text) in &mut texts {
+        let Ok(button) = buttons.get(parent.get()) else {
+            continue;
+        };
+
+        let charges = spell_book
+            .get(&button.spell_id, &definitions)
+            .and_then(|spell| cooldowns.charges(&button.spell_id, spell));
+
+        if let Some(section) = text.sections.get_mut(0) {
+            section.value.clear();
+
+            if let Some(charges) = charges {
+                section.value = charges.to_string();
+            }
+        }
+    }
+}
+
+/// Outlines the button of the spell that is queued up next.
+pub fn update_action_bar_queued(
+    player: Query<Option<&QueuedSpell>, With<Player>>,
+    mut buttons: Query<(&ActionBarButton, &mut BorderColor)>,
+) {
+    let Ok(queued) = player.get_single() else {
+        return;
+    };
+
+    for (button, mut border_color) in &mut buttons {
+        let is_queued = queued.is_some_and(|queued| queued.spell_id == button.spell_id);
+
+        *border_color = if is_queued {
+            Color::YELLOW.into()
+        } else {
+            Color::BLACK.into()
+        };
+    }
 }
@@ -0,0 +1,347 @@
+use bevy::prelude::*;
+
+use crate::{
+    auras::{AuraEffect, AuraInstance, Auras, DamageChangeDirection, DamageChangeType},
+    character_controller::Player,
+    crowd_control::CrowdControl,
+    pulse::PulseTargets,
+    spells::{CurrentTarget, SpellBook, SpellDefinition},
+};
+
+use super::{tooltip::ShowsTooltip, BUTTON_SIZE};
+
+/// How many auras a single bar shows at most.
+static AURA_BAR_SLOTS: usize = 12;
+
+/// Whose auras a bar shows: the player's buffs, or the debuffs on their target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuraBarUnit {
+    Player,
+    Target,
+}
+
+impl AuraBarUnit {
+    fn shows(&self, aura: &AuraInstance) -> bool {
+        match self {
+            AuraBarUnit::Player => !aura.harmful,
+            AuraBarUnit::Target => aura.harmful,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct AuraBar {
+    pub unit: AuraBarUnit,
+}
+
+/// A slot on an aura bar, showing the aura at `index` on the unit (if there is one).
+#[derive(Component)]
+pub struct AuraIcon {
+    pub index: usize,
+}
+
+/// The texts on top of an aura icon.
+#[derive(Component)]
+pub enum AuraIconText {
+    /// Short version of the aura's name, in place of an actual icon.
+    Label,
+    /// Seconds left.
+    Duration,
+    Stacks,
+}
+
+fn spawn_aura_icon_text(
+    parent: &mut ChildBuilder,
+    kind: AuraIconText,
+    style: Style,
+    font_size: f32,
+    color: Color,
+    asset_server: &Res<AssetServer>,
+) {
+    parent.spawn((
+        TextBundle {
+            style,
+            text: Text {
+                sections: vec![TextSection {
+                    value: String::with_capacity(8),
+                    style: TextStyle {
+                        font: asset_server.load("Rosela.ttf"),
+                        font_size,
+                        color,
+                    },
+                }],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        kind,
+    ));
+}
+
+fn spawn_aura_icon(parent: &mut ChildBuilder, index: usize, asset_server: &Res<AssetServer>) {
+    parent
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Px(BUTTON_SIZE),
+                    height: Val::Px(BUTTON_SIZE),
+                    margin: UiRect::all(Val::Px(1.0)),
+                    border: UiRect::all(Val::Px(1.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    display: Display::None,
+                    ..Default::default()
+                },
+                background_color: Color::BLACK.into(),
+                border_color: Color::BLACK.into(),
+                ..default()
+            },
+            ShowsTooltip {
+                title: String::new(),
+                description: String::new(),
+            },
+            AuraIcon { index },
+            Interaction::default(),
+        ))
+        .with_children(|parent| {
+            spawn_aura_icon_text(
+                parent,
+                AuraIconText::Label,
+                Style::default(),
+                14.0,
+                Color::WHITE,
+                asset_server,
+            );
+
+            spawn_aura_icon_text(
+                parent,
+                AuraIconText::Duration,
+                Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(0.0),
+                    left: Val::Px(1.0),
+                    ..Default::default()
+                },
+                10.0,
+                Color::YELLOW,
+                asset_server,
+            );
+
+            spawn_aura_icon_text(
+                parent,
+                AuraIconText::Stacks,
+                Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.0),
+                    right: Val::Px(1.0),
+                    ..Default::default()
+                },
+                10.0,
+                Color::WHITE,
+                asset_server,
+            );
+        });
+}
+
+/// A row of aura icons, for the player's buffs or the debuffs on their current target.
+pub fn spawn_aura_bar(
+    parent: &mut ChildBuilder,
+    unit: AuraBarUnit,
+    style: Style,
+    asset_server: &Res<AssetServer>,
+) {
+    parent
+        .spawn((
+            NodeBundle {
+                style,
+                ..Default::default()
+            },
+            AuraBar { unit },
+        ))
+        .with_children(|parent| {
+            for index in 0..AURA_BAR_SLOTS {
+                spawn_aura_icon(parent, index, asset_server);
+            }
+        });
+}
+
+/// The player's buffs go in the top right corner, newest on the outside.
+pub fn setup_player_aura_bar(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                right: Val::Px(10.0),
+                top: Val::Px(10.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            spawn_aura_bar(
+                parent,
+                AuraBarUnit::Player,
+                Style {
+                    flex_direction: FlexDirection::RowReverse,
+                    ..Default::default()
+                },
+                &asset_server,
+            )
+        });
+}
+
+/// Shows, hides and fills in every aura icon from the auras on the bar's unit.
+pub fn update_aura_bars(
+    player: Query<(Entity, &CurrentTarget), With<Player>>,
+    units: Query<&Auras>,
+    spell_book: Res<SpellBook>,
+    definitions: Res<Assets<SpellDefinition>>,
+    bars: Query<&AuraBar>,
+    mut icons: Query<(
+        &AuraIcon,
+        &Parent,
+        &Children,
+        &mut Style,
+        &mut BorderColor,
+        &mut ShowsTooltip,
+    )>,
+    mut texts: Query<(&AuraIconText, &mut Text)>,
+) {
+    let Ok((player, current_target)) = player.get_single() else {
+        return;
+    };
+
+    for (icon, parent, children, mut style, mut border_color, mut tooltip) in &mut icons {
+        let Ok(bar) = bars.get(parent.get()) else {
+            continue;
+        };
+
+        let unit = match bar.unit {
+            AuraBarUnit::Player => Some(player),
+            AuraBarUnit::Target => current_target.0,
+        };
+
+        let aura = unit
+            .and_then(|unit| units.get(unit).ok())
+            .and_then(|auras| {
+                auras
+                    .iter()
+                    .filter(|aura| bar.unit.shows(aura))
+                    .nth(icon.index)
+            });
+
+        let Some(aura) = aura else {
+            style.display = Display::None;
+            continue;
+        };
+
+        style.display = Display::Flex;
+
+        *border_color = if aura.harmful {
+            Color::RED.into()
+        } else {
+            Color::GREEN.into()
+        };
+
+        // auras from triggers aren't in the spell book, their spell id is the trigger's name
+        let name = spell_book
+            .get(&aura.spell_id, &definitions)
+            .map_or(&aura.spell_id.0, |spell| &spell.name);
+
+        tooltip.title = name.clone();
+        tooltip.description = describe(aura);
+
+        for child in children.iter() {
+            let Ok((kind, mut text)) = texts.get_mut(*child) else {
+                continue;
+            };
+
+            let Some(section) = text.sections.get_mut(0) else {
+                continue;
+            };
+
+            section.value = match kind {
+                AuraIconText::Label => name.chars().take(2).collect(),
+                AuraIconText::Duration => {
+                    let remaining = aura.duration.remaining_secs();
+
+                    if remaining >= 10.0 {
+                        format!("{:.0}", remaining)
+                    } else {
+                        format!("{:.1}", remaining)
+                    }
+                }
+                AuraIconText::Stacks if aura.stacks > 1 => aura.stacks.to_string(),
+                AuraIconText::Stacks => String::new(),
+            };
+        }
+    }
+}
+
+/// What the aura currently does, for its tooltip.
+fn describe(aura: &AuraInstance) -> String {
+    match &aura.effect {
+        AuraEffect::Movement {
+            percentage_change,
+            increase,
+        } => format!(
+            "Movement speed {} by {}%.",
+            if *increase { "increased" } else { "reduced" },
+            (*percentage_change as u32 * aura.stacks).min(100)
+        ),
+        AuraEffect::Overtime {
+            amount,
+            healing,
+            every,
+        } => format!(
+            "{} {} {} every {:.1} seconds.",
+            if *healing { "Heals" } else { "Deals" },
+            amount * aura.stacks,
+            if *healing { "health" } else { "damage" },
+            every.as_secs_f32()
+        ),
+        AuraEffect::DamageChange {
+            direction,
+            amount,
+            value_type,
+        } => format!(
+            "Damage {} {} by {}{}.",
+            match direction {
+                DamageChangeDirection::Dealt => "dealt",
+                DamageChangeDirection::Taken => "taken",
+            },
+            if *amount < 0 { "reduced" } else { "increased" },
+            amount.unsigned_abs() * aura.stacks,
+            match value_type {
+                DamageChangeType::Percent => "%",
+                DamageChangeType::Amount => "",
+            }
+        ),
+        AuraEffect::Haste { percentage } => {
+            format!("Haste increased by {}%.", percentage * aura.stacks)
+        }
+        AuraEffect::CrowdControl(crowd_control) => match crowd_control {
+            CrowdControl::Stun => "Stunned.",
+            CrowdControl::Root => "Rooted.",
+            CrowdControl::Silence => "Silenced.",
+            CrowdControl::Fear => "Feared.",
+        }
+        .to_string(),
+        AuraEffect::Absorb { .. } => format!("Absorbs {} more damage.", aura.absorb_left),
+        AuraEffect::Pulse {
+            radius,
+            every,
+            targets,
+            ..
+        } => format!(
+            "Affects {} within {} yards every {:.1} seconds.",
+            match targets {
+                PulseTargets::Allies => "allies",
+                PulseTargets::Enemies => "enemies",
+                PulseTargets::Everyone => "everyone",
+            },
+            radius,
+            every.as_secs_f32()
+        ),
+    }
+}
@@ -1,8 +1,13 @@
 use bevy::prelude::*;
 
-use crate::spells::Casting;
+use crate::{
+    character_controller::Player,
+    crowd_control::CrowdControl,
+    spells::{CastInterruptReason, CastInterrupted, Casting, SpellBook, SpellDefinition},
+};
 
 static CAST_BAR_SIZE_IN_PX: f32 = 100.0;
+static CAST_BAR_FLASH_IN_SECS: f32 = 0.5;
 
 #[derive(Component)]
 pub struct CastBar;
@@ -10,9 +15,18 @@ pub struct CastBar;
 #[derive(Component)]
 pub struct CastBarInner;
 
+#[derive(Component)]
+pub struct CastBarText;
+
+/// Keeps the cast bar red (and visible) for a moment after a cast is interrupted.
+#[derive(Component, Default)]
+pub struct CastBarFlash {
+    timer: Option<Timer>,
+}
+
 pub fn setup_cast_bar(
     mut commands: Commands,
-    _asset_server: Res<AssetServer>,
+    asset_server: Res<AssetServer>,
     window: Query<&Window>,
 ) {
     let window = window.single();
@@ -28,6 +42,7 @@ pub fn setup_cast_bar(
                     top: Val::Percent(75.0),
                     // padding: UiRect::all(Val::Px(10.0)),
                     flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
                     // overflow: Overflow::
                     ..Default::default()
                 },
@@ -36,6 +51,7 @@ pub fn setup_cast_bar(
                 ..Default::default()
             },
             CastBar,
+            CastBarFlash::default(),
         ))
         .with_children(|parent| {
             parent.spawn((
@@ -44,6 +60,7 @@ pub fn setup_cast_bar(
                         width: Val::Px(0.0),
                         height: Val::Percent(100.0),
                         flex_direction: FlexDirection::Column,
+                        align_self: AlignSelf::Start,
                         // padding: UiRect::all(Val::Px(10.0)),
                         ..Default::default()
                     },
@@ -52,20 +69,63 @@ pub fn setup_cast_bar(
                 },
                 CastBarInner,
             ));
+
+            parent.spawn((
+                TextBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        ..Default::default()
+                    },
+                    text: Text {
+                        sections: vec![TextSection {
+                            value: String::with_capacity(32),
+                            style: TextStyle {
+                                font: asset_server.load("Rosela.ttf"),
+                                font_size: 14.0,
+                                color: Color::WHITE,
+                            },
+                        }],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                CastBarText,
+            ));
         });
 }
 
 pub fn update_cast_bar(
     caster: Query<&Casting>,
+    cast_bar: Query<&CastBarFlash>,
+    spell_book: Res<SpellBook>,
+    definitions: Res<Assets<SpellDefinition>>,
     mut bar_inner: Query<&mut Style, With<CastBarInner>>,
+    mut bar_text: Query<&mut Text, With<CastBarText>>,
 ) {
+    // while flashing, the bar shows the interruption instead
+    if cast_bar.iter().any(|flash| flash.timer.is_some()) {
+        return;
+    }
+
     for casting in caster.iter() {
-        let casting_percent: f32 = (casting.current_duration.as_millis() * 100
-            / casting.total_duration.as_millis()) as f32; // should always be between 0 and 100
+        let mut casting_percent: f32 = (casting.current_duration.as_millis() * 100
+            / casting.total_duration.as_millis().max(1))
+            as f32; // should always be between 0 and 100
+
+        // channels drain instead of fill
+        if casting.is_channel() {
+            casting_percent = 100.0 - casting_percent;
+        }
 
         for mut bar_inner in &mut bar_inner {
             bar_inner.width = Val::Percent(casting_percent);
         }
+
+        if let Some(spell) = spell_book.get(&casting.spell_id, &definitions) {
+            for mut text in &mut bar_text {
+                text.sections[0].value.clone_from(&spell.name);
+            }
+        }
     }
 }
 
@@ -82,11 +142,81 @@ pub fn update_cast_bar_visible(
 
 pub fn update_cast_bar_invisible(
     mut removed: RemovedComponents<Casting>,
-    mut cast_bar: Query<&mut Visibility, With<CastBar>>,
+    mut cast_bar: Query<(&mut Visibility, &CastBarFlash), With<CastBar>>,
 ) {
     for _ in removed.read() {
-        for mut cast_bar in &mut cast_bar {
-            *cast_bar = Visibility::Hidden
+        for (mut cast_bar, flash) in &mut cast_bar {
+            if flash.timer.is_none() {
+                *cast_bar = Visibility::Hidden
+            }
+        }
+    }
+}
+
+pub fn update_cast_bar_interrupted(
+    mut cast_interrupted_events: EventReader<CastInterrupted>,
+    player: Query<Entity, With<Player>>,
+    mut cast_bar: Query<(&mut Visibility, &mut CastBarFlash), With<CastBar>>,
+    mut bar_inner: Query<(&mut Style, &mut BackgroundColor), With<CastBarInner>>,
+    mut bar_text: Query<&mut Text, With<CastBarText>>,
+) {
+    for event in cast_interrupted_events.read() {
+        if player.get(event.caster).is_err() {
+            continue;
+        }
+
+        for (mut visibility, mut flash) in &mut cast_bar {
+            *visibility = Visibility::Visible;
+            flash.timer = Some(Timer::from_seconds(CAST_BAR_FLASH_IN_SECS, TimerMode::Once));
+        }
+
+        for (mut style, mut color) in &mut bar_inner {
+            *color = Color::RED.into();
+
+            if event.reason.cancels_cast() {
+                style.width = Val::Percent(100.0);
+            }
+        }
+
+        let reason = match event.reason {
+            CastInterruptReason::Moved => "Moved",
+            CastInterruptReason::PushedBack => "Pushed back",
+            CastInterruptReason::Interrupted => "Interrupted",
+            CastInterruptReason::CrowdControlled(CrowdControl::Stun) => "Stunned",
+            CastInterruptReason::CrowdControlled(CrowdControl::Root) => "Rooted",
+            CastInterruptReason::CrowdControlled(CrowdControl::Silence) => "Silenced",
+            CastInterruptReason::CrowdControlled(CrowdControl::Fear) => "Feared",
+        };
+
+        for mut text in &mut bar_text {
+            text.sections[0].value = reason.to_string();
+        }
+    }
+}
+
+pub fn update_cast_bar_flash(
+    time: Res<Time>,
+    caster: Query<&Casting, With<Player>>,
+    mut cast_bar: Query<(&mut Visibility, &mut CastBarFlash), With<CastBar>>,
+    mut bar_inner: Query<&mut BackgroundColor, With<CastBarInner>>,
+) {
+    for (mut visibility, mut flash) in &mut cast_bar {
+        let Some(timer) = &mut flash.timer else {
+            continue;
+        };
+
+        if !timer.tick(time.delta()).finished() {
+            continue;
+        }
+
+        flash.timer = None;
+
+        for mut color in &mut bar_inner {
+            *color = Color::BLUE.into();
+        }
+
+        if caster.is_empty() {
+            *visibility = Visibility::Hidden;
         }
     }
 }
@@ -0,0 +1,136 @@
+use bevy::prelude::*;
+
+use crate::combat_log::{CombatLog, CombatLogKind};
+
+/// How many entries the panel shows at once.
+static COMBAT_LOG_LINES: usize = 12;
+static COMBAT_LOG_WIDTH_IN_PX: f32 = 520.0;
+
+/// The combat log panel, toggled with L and scrolled with Page Up, Page Down and End.
+#[derive(Component)]
+pub struct CombatLogPanel {
+    /// How many entries the view is scrolled up from the newest one.
+    pub scroll: usize,
+}
+
+#[derive(Component)]
+pub struct CombatLogText;
+
+pub fn setup_combat_log_panel(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(10.0),
+                    bottom: Val::Px(10.0),
+                    width: Val::Px(COMBAT_LOG_WIDTH_IN_PX),
+                    padding: UiRect::all(Val::Px(4.0)),
+                    display: Display::None,
+                    ..Default::default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                ..Default::default()
+            },
+            CombatLogPanel { scroll: 0 },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle {
+                    text: Text {
+                        sections: (0..COMBAT_LOG_LINES)
+                            .map(|_| TextSection {
+                                value: String::new(),
+                                style: TextStyle {
+                                    font: asset_server.load("Rosela.ttf"),
+                                    font_size: 12.0,
+                                    color: Color::WHITE,
+                                },
+                            })
+                            .collect(),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                CombatLogText,
+            ));
+        });
+}
+
+fn kind_color(kind: CombatLogKind) -> Color {
+    match kind {
+        CombatLogKind::Cast => Color::YELLOW,
+        CombatLogKind::Damage => Color::ORANGE_RED,
+        CombatLogKind::Heal => Color::GREEN,
+        CombatLogKind::Aura => Color::CYAN,
+        CombatLogKind::Death => Color::GRAY,
+    }
+}
+
+pub fn combat_log_panel_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    log: Res<CombatLog>,
+    mut panel: Query<(&mut CombatLogPanel, &mut Style)>,
+) {
+    let Ok((mut panel, mut style)) = panel.get_single_mut() else {
+        return;
+    };
+
+    if keyboard_input.just_pressed(KeyCode::L) {
+        style.display = match style.display {
+            Display::None => Display::Flex,
+            _ => Display::None,
+        };
+    }
+
+    let max_scroll = log.iter().len().saturating_sub(COMBAT_LOG_LINES);
+
+    if keyboard_input.just_pressed(KeyCode::PageUp) {
+        panel.scroll = (panel.scroll + COMBAT_LOG_LINES).min(max_scroll);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::PageDown) {
+        panel.scroll = panel.scroll.saturating_sub(COMBAT_LOG_LINES);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::End) {
+        panel.scroll = 0;
+    }
+}
+
+/// Shows the newest entries, or older ones when scrolled up.
+pub fn update_combat_log_panel(
+    log: Res<CombatLog>,
+    panel: Query<(&CombatLogPanel, &Style)>,
+    mut text: Query<&mut Text, With<CombatLogText>>,
+) {
+    let Ok((panel, style)) = panel.get_single() else {
+        return;
+    };
+
+    if style.display == Display::None {
+        return;
+    }
+
+    let Ok(mut text) = text.get_single_mut() else {
+        return;
+    };
+
+    let mut entries: Vec<_> = log
+        .iter()
+        .rev()
+        .skip(panel.scroll)
+        .take(COMBAT_LOG_LINES)
+        .collect();
+    entries.reverse();
+
+    for (index, section) in text.sections.iter_mut().enumerate() {
+        match entries.get(index) {
+            Some(entry) => {
+                section.value = format!("{}\n", entry.line());
+                section.style.color = kind_color(entry.kind);
+            }
+            None => section.value.clear(),
+        }
+    }
+}
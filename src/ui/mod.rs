@@ -1,16 +1,28 @@
 use bevy::prelude::*;
 
 use self::{
-    action_bar::setup_action_bar,
+    action_bar::{
+        setup_action_bar, update_action_bar_charges, update_action_bar_cooldowns,
+        update_action_bar_queued, update_action_bar_tooltips,
+    },
+    aura_bar::{setup_player_aura_bar, update_aura_bars},
     cast_bar::{
-        setup_cast_bar, update_cast_bar, update_cast_bar_invisible, update_cast_bar_visible,
+        setup_cast_bar, update_cast_bar, update_cast_bar_flash, update_cast_bar_interrupted,
+        update_cast_bar_invisible, update_cast_bar_visible,
     },
+    combat_log::{combat_log_panel_input, setup_combat_log_panel, update_combat_log_panel},
     tooltip::{mouseover_system, setup_tooltip, tooltip_events, TooltipState},
+    unit_frame::{
+        setup_player_frame, setup_target_frame, update_player_frame, update_target_frame,
+    },
 };
 
 mod action_bar;
+mod aura_bar;
 mod cast_bar;
+mod combat_log;
 mod tooltip;
+mod unit_frame;
 
 static BUTTON_SIZE: f32 = 30.0;
 
@@ -46,7 +58,17 @@ impl Plugin for UIPlugin {
     fn build(&self, app: &mut App) {
         // app.init_resource::<TooltipState>();
         app.add_event::<TooltipState>();
-        app.add_systems(Startup, (setup_ui, setup_cast_bar));
+        app.add_systems(
+            Startup,
+            (
+                setup_ui,
+                setup_cast_bar,
+                setup_player_frame,
+                setup_target_frame,
+                setup_player_aura_bar,
+                setup_combat_log_panel,
+            ),
+        );
 
         app.add_systems(
             Update,
@@ -56,7 +78,21 @@ impl Plugin for UIPlugin {
                 update_cast_bar,
                 update_cast_bar_visible,
                 update_cast_bar_invisible,
+                update_cast_bar_interrupted,
+                update_cast_bar_flash,
+                update_action_bar_tooltips,
+                update_action_bar_cooldowns,
+                update_action_bar_charges,
+                update_action_bar_queued,
+                update_player_frame,
+                update_target_frame,
+                update_aura_bars,
             ),
         );
+
+        app.add_systems(
+            Update,
+            (combat_log_panel_input, update_combat_log_panel).chain(),
+        );
     }
 }
@@ -0,0 +1,257 @@
+use bevy::prelude::*;
+
+use crate::{
+    character_controller::Player, health::Health, resource_pool::ResourcePool,
+    spells::CurrentTarget,
+};
+
+use super::aura_bar::{spawn_aura_bar, AuraBarUnit};
+
+static UNIT_FRAME_WIDTH_IN_PX: f32 = 200.0;
+static UNIT_FRAME_BAR_HEIGHT_IN_PX: f32 = 16.0;
+
+#[derive(Component)]
+pub struct PlayerFrame;
+
+#[derive(Component)]
+pub struct HealthFill;
+
+#[derive(Component)]
+pub struct HealthFillText;
+
+#[derive(Component)]
+pub struct ResourceFill;
+
+#[derive(Component)]
+pub struct ResourceFillText;
+
+#[derive(Component)]
+pub struct TargetFrame;
+
+#[derive(Component)]
+pub struct TargetHealthFill;
+
+#[derive(Component)]
+pub struct TargetHealthFillText;
+
+fn spawn_bar(
+    parent: &mut ChildBuilder,
+    color: Color,
+    fill: impl Component,
+    text: impl Component,
+    asset_server: &Res<AssetServer>,
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(UNIT_FRAME_WIDTH_IN_PX),
+                height: Val::Px(UNIT_FRAME_BAR_HEIGHT_IN_PX),
+                margin: UiRect::bottom(Val::Px(2.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            background_color: Color::DARK_GRAY.into(),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(0.0),
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        ..Default::default()
+                    },
+                    background_color: color.into(),
+                    ..Default::default()
+                },
+                fill,
+            ));
+
+            parent.spawn((
+                TextBundle {
+                    text: Text {
+                        sections: vec![TextSection {
+                            value: String::with_capacity(16),
+                            style: TextStyle {
+                                font: asset_server.load("Rosela.ttf"),
+                                font_size: 12.0,
+                                color: Color::WHITE,
+                            },
+                        }],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                text,
+            ));
+        });
+}
+
+pub fn setup_player_frame(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(10.0),
+                    top: Val::Px(10.0),
+                    padding: UiRect::all(Val::Px(4.0)),
+                    flex_direction: FlexDirection::Column,
+                    ..Default::default()
+                },
+                background_color: Color::BLACK.into(),
+                ..Default::default()
+            },
+            PlayerFrame,
+        ))
+        .with_children(|parent| {
+            spawn_bar(
+                parent,
+                Color::GREEN,
+                HealthFill,
+                HealthFillText,
+                &asset_server,
+            );
+            spawn_bar(
+                parent,
+                Color::BLUE,
+                ResourceFill,
+                ResourceFillText,
+                &asset_server,
+            );
+        });
+}
+
+pub fn update_player_frame(
+    player: Query<(&Health, Option<&ResourcePool>), With<Player>>,
+    mut health_fill: Query<&mut Style, (With<HealthFill>, Without<ResourceFill>)>,
+    mut resource_fill: Query<
+        (&mut Style, &mut BackgroundColor, &mut Visibility),
+        (With<ResourceFill>, Without<HealthFill>),
+    >,
+    mut health_text: Query<&mut Text, (With<HealthFillText>, Without<ResourceFillText>)>,
+    mut resource_text: Query<&mut Text, (With<ResourceFillText>, Without<HealthFillText>)>,
+) {
+    let Ok((health, pool)) = player.get_single() else {
+        return;
+    };
+
+    for mut style in &mut health_fill {
+        style.width = Val::Percent(percent(health.current, health.max));
+    }
+
+    for mut text in &mut health_text {
+        text.sections[0].value = format!("{}/{}", health.current, health.max);
+    }
+
+    for (mut style, mut color, mut visibility) in &mut resource_fill {
+        if let Some(pool) = pool {
+            style.width = Val::Percent(percent(pool.current, pool.max));
+            *color = pool.resource_type.color().into();
+            *visibility = Visibility::Inherited;
+        } else {
+            *visibility = Visibility::Hidden;
+        }
+    }
+
+    for mut text in &mut resource_text {
+        text.sections[0].value = pool
+            .map(|pool| format!("{}/{}", pool.current, pool.max))
+            .unwrap_or_default();
+    }
+}
+
+/// Sits next to the player frame, with the target's auras underneath.
+pub fn setup_target_frame(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(UNIT_FRAME_WIDTH_IN_PX + 30.0),
+                    top: Val::Px(10.0),
+                    flex_direction: FlexDirection::Column,
+                    ..Default::default()
+                },
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            },
+            TargetFrame,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        padding: UiRect::all(Val::Px(4.0)),
+                        ..Default::default()
+                    },
+                    background_color: Color::BLACK.into(),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    spawn_bar(
+                        parent,
+                        Color::RED,
+                        TargetHealthFill,
+                        TargetHealthFillText,
+                        &asset_server,
+                    );
+                });
+
+            spawn_aura_bar(
+                parent,
+                AuraBarUnit::Target,
+                Style {
+                    max_width: Val::Px(UNIT_FRAME_WIDTH_IN_PX + 8.0),
+                    flex_wrap: FlexWrap::Wrap,
+                    ..Default::default()
+                },
+                &asset_server,
+            );
+        });
+}
+
+pub fn update_target_frame(
+    player: Query<&CurrentTarget, With<Player>>,
+    units: Query<&Health>,
+    mut target_frame: Query<&mut Visibility, With<TargetFrame>>,
+    mut health_fill: Query<&mut Style, With<TargetHealthFill>>,
+    mut health_text: Query<&mut Text, With<TargetHealthFillText>>,
+) {
+    let health = player
+        .get_single()
+        .ok()
+        .and_then(|current_target| current_target.0)
+        .and_then(|target| units.get(target).ok());
+
+    for mut visibility in &mut target_frame {
+        *visibility = if health.is_some() {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+
+    let Some(health) = health else {
+        return;
+    };
+
+    for mut style in &mut health_fill {
+        style.width = Val::Percent(percent(health.current, health.max));
+    }
+
+    for mut text in &mut health_text {
+        text.sections[0].value = format!("{}/{}", health.current, health.max);
+    }
+}
+
+fn percent(current: u32, max: u32) -> f32 {
+    if max == 0 {
+        return 0.0;
+    }
+
+    current as f32 * 100.0 / max as f32
+}
@@ -1,3 +1,5 @@
+use bevy::math::Vec3;
+
 pub fn safe_minus(one: f32, two: f32) -> f32 {
     if one >= two {
         one - two
@@ -5,3 +7,8 @@ pub fn safe_minus(one: f32, two: f32) -> f32 {
         two - one
     }
 }
+
+/// Distance between two points along the xz plane, ignoring height.
+pub fn xz_distance(one: Vec3, two: Vec3) -> f32 {
+    (safe_minus(one.z, two.z).powi(2) + safe_minus(one.x, two.x).powi(2)).sqrt()
+}